@@ -1,54 +1,130 @@
 use async_trait::async_trait;
 use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
 use crate::error::ServerError;
 use super::{AuthProvider, AuthData};
 
+/// An API key stored as a salted SHA-256 digest rather than plaintext.
+#[derive(Clone)]
+struct StoredKey {
+    salt: [u8; 16],
+    digest: [u8; 32],
+    user_id: String,
+    scopes: Vec<String>,
+}
+
+/// The user and scopes an API key is allowed to act as/with.
+#[derive(Clone, Debug)]
+pub struct ApiKeyGrant {
+    pub user_id: String,
+    pub scopes: Vec<String>,
+}
+
+impl ApiKeyGrant {
+    pub fn new(user_id: impl Into<String>, scopes: Vec<String>) -> Self {
+        Self { user_id: user_id.into(), scopes }
+    }
+}
+
+impl From<&str> for ApiKeyGrant {
+    /// Build a grant with no scopes, for callers that only care about `user_id`.
+    fn from(user_id: &str) -> Self {
+        Self { user_id: user_id.to_string(), scopes: Vec::new() }
+    }
+}
+
+fn hash_key(salt: &[u8; 16], credential: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(credential.as_bytes());
+    hasher.finalize().into()
+}
+
 /// Simple API key authentication provider
 #[derive(Clone)]
 pub struct ApiKeyAuthService {
-    /// Map of API key to user ID
-    api_keys: HashMap<String, String>,
+    /// Salted digests of every known API key, alongside the grant it maps to.
+    keys: Vec<StoredKey>,
 }
 
 impl ApiKeyAuthService {
-    /// Create a new API key auth service from environment variables
-    /// Expects API_KEYS env var in format: "key1:user1,key2:user2"
+    /// Create a new API key auth service from environment variables.
+    /// Expects API_KEYS env var in format: "key1:user1:scope1|scope2,key2:user2"
+    /// (the scopes segment is optional and grants no scopes when omitted).
     pub fn from_env() -> Result<Self, anyhow::Error> {
         let mut api_keys = HashMap::new();
-        
+
         if let Ok(keys_str) = std::env::var("API_KEYS") {
             for pair in keys_str.split(',') {
                 let parts: Vec<&str> = pair.split(':').collect();
-                if parts.len() == 2 {
-                    api_keys.insert(parts[0].to_string(), parts[1].to_string());
+                if parts.len() >= 2 {
+                    let scopes = parts
+                        .get(2)
+                        .map(|s| s.split('|').map(String::from).collect())
+                        .unwrap_or_default();
+                    api_keys.insert(parts[0].to_string(), ApiKeyGrant::new(parts[1], scopes));
                 }
             }
         }
-        
-        Ok(Self { api_keys })
+
+        Ok(Self::new(api_keys))
     }
-    
-    /// Create a new API key auth service with predefined keys
-    pub fn new(api_keys: HashMap<String, String>) -> Self {
-        Self { api_keys }
+
+    /// Create a new API key auth service from plaintext `key -> grant` pairs,
+    /// hashing each key at construction time.
+    pub fn new(api_keys: HashMap<String, ApiKeyGrant>) -> Self {
+        let keys = api_keys
+            .into_iter()
+            .map(|(key, grant)| {
+                let salt: [u8; 16] = rand::random();
+                let digest = hash_key(&salt, &key);
+                StoredKey { salt, digest, user_id: grant.user_id, scopes: grant.scopes }
+            })
+            .collect();
+
+        Self { keys }
+    }
+
+    /// Create a service directly from pre-hashed entries, e.g. ones produced by
+    /// `gen-apikey` and stored in config rather than as plaintext.
+    pub fn from_hashed(entries: Vec<([u8; 16], [u8; 32], ApiKeyGrant)>) -> Self {
+        let keys = entries
+            .into_iter()
+            .map(|(salt, digest, grant)| StoredKey { salt, digest, user_id: grant.user_id, scopes: grant.scopes })
+            .collect();
+
+        Self { keys }
     }
 }
 
 #[async_trait]
 impl AuthProvider for ApiKeyAuthService {
     async fn authenticate(&self, credential: &str) -> Result<AuthData, ServerError> {
-        // For API key auth, the credential is the API key itself
-        let user_id = self.api_keys.get(credential)
+        // Hash the presented credential against every stored entry so the
+        // comparison cost - and thus its timing - never depends on which key
+        // (if any) matched.
+        let mut matched: Option<&StoredKey> = None;
+        for stored in &self.keys {
+            let digest = hash_key(&stored.salt, credential);
+            let is_match: bool = digest.ct_eq(&stored.digest).into();
+            if is_match && matched.is_none() {
+                matched = Some(stored);
+            }
+        }
+
+        let stored = matched
             .ok_or_else(|| ServerError::InvalidSession("Invalid API key".to_string()))?;
-        
+
         Ok(AuthData {
-            user_id: user_id.clone(),
+            user_id: stored.user_id.clone(),
             metadata: serde_json::json!({
                 "auth_type": "api_key"
             }),
+            scopes: stored.scopes.clone(),
         })
     }
-    
+
     fn validate_credential_format(&self, credential: &str) -> Result<(), ServerError> {
         if credential.is_empty() {
             return Err(ServerError::InvalidSession("API key cannot be empty".to_string()));
@@ -60,31 +136,47 @@ impl AuthProvider for ApiKeyAuthService {
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[tokio::test]
     async fn test_api_key_auth() {
         let mut keys = HashMap::new();
-        keys.insert("test-key-123".to_string(), "user123".to_string());
-        keys.insert("admin-key-456".to_string(), "admin456".to_string());
-        
+        keys.insert("test-key-123".to_string(), ApiKeyGrant::new("user123", vec!["read".to_string()]));
+        keys.insert("admin-key-456".to_string(), ApiKeyGrant::new("admin456", vec!["read".to_string(), "write".to_string()]));
+
         let auth = ApiKeyAuthService::new(keys);
-        
+
         // Test valid key
         let result = auth.authenticate("test-key-123").await;
         assert!(result.is_ok());
         let auth_data = result.unwrap();
         assert_eq!(auth_data.user_id, "user123");
-        
+        assert!(auth_data.has_scope("read"));
+        assert!(!auth_data.has_scope("write"));
+
         // Test invalid key
         let result = auth.authenticate("invalid-key").await;
         assert!(result.is_err());
     }
-    
+
+    #[tokio::test]
+    async fn test_keys_are_not_stored_as_plaintext() {
+        let mut keys = HashMap::new();
+        keys.insert("super-secret-key".to_string(), ApiKeyGrant::from("user123"));
+
+        let auth = ApiKeyAuthService::new(keys);
+
+        assert!(auth.keys.iter().all(|k| k.digest != [0u8; 32]));
+        assert!(auth
+            .keys
+            .iter()
+            .all(|k| hash_key(&k.salt, "super-secret-key") == k.digest));
+    }
+
     #[test]
     fn test_validate_format() {
         let auth = ApiKeyAuthService::new(HashMap::new());
-        
+
         assert!(auth.validate_credential_format("valid-key").is_ok());
         assert!(auth.validate_credential_format("").is_err());
     }
-}
\ No newline at end of file
+}
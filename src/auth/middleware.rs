@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use axum::extract::Request;
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use super::{AuthData, AuthProvider};
+
+tokio::task_local! {
+    /// The `AuthData` for the request currently being handled, if any. Tool
+    /// handlers read this via `current()` to learn who is calling them.
+    static CURRENT_AUTH: Option<AuthData>;
+}
+
+/// Read the `AuthData` for the in-flight request, if the active provider
+/// authenticated one. Returns `None` outside of a request (e.g. STDIO
+/// transport, which has no per-request identity) or when auth is disabled.
+pub fn current() -> Option<AuthData> {
+    CURRENT_AUTH.try_with(|auth| auth.clone()).ok().flatten()
+}
+
+/// Extract a credential from the incoming request: a bearer token from the
+/// `Authorization` header, falling back to the MCP session id header.
+fn extract_credential(req: &Request) -> Option<String> {
+    if let Some(value) = req.headers().get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()) {
+        if let Some(token) = value.strip_prefix("Bearer ") {
+            return Some(token.to_string());
+        }
+    }
+
+    req.headers()
+        .get("Mcp-Session-Id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string())
+}
+
+fn unauthorized(message: impl Into<String>) -> Response {
+    (StatusCode::UNAUTHORIZED, message.into()).into_response()
+}
+
+/// Axum middleware that authenticates every request against the configured
+/// `AuthProvider`, rejecting with 401 on failure and stashing the resulting
+/// `AuthData` into request extensions on success so tool handlers can read it.
+pub async fn authenticate(provider: Arc<dyn AuthProvider>, mut req: Request, next: Next) -> Response {
+    if !provider.requires_credential() {
+        return next.run(req).await;
+    }
+
+    let Some(credential) = extract_credential(&req) else {
+        return unauthorized("Missing credential (Authorization: Bearer <token> or Mcp-Session-Id)");
+    };
+
+    if let Err(e) = provider.validate_credential_format(&credential) {
+        return unauthorized(format!("Invalid credential: {}", e));
+    }
+
+    match provider.authenticate(&credential).await {
+        Ok(auth_data) => {
+            req.extensions_mut().insert(auth_data.clone());
+            CURRENT_AUTH.scope(Some(auth_data), next.run(req)).await
+        }
+        Err(e) => unauthorized(format!("Authentication failed: {}", e)),
+    }
+}
@@ -1,5 +1,7 @@
+use std::sync::Arc;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use crate::config::{AuthProviderKind, Config};
 use crate::error::ServerError;
 
 #[cfg(feature = "auth-redis")]
@@ -7,23 +9,71 @@ pub mod redis;
 
 #[cfg(feature = "auth-redis")]
 pub use redis::{RedisAuthService, SessionData, OAuthTokenData};
+#[cfg(feature = "auth-redis")]
+pub use redis::RedisAuthService as AuthService;
+
+#[cfg(feature = "auth-redis")]
+pub mod oauth;
+#[cfg(feature = "auth-redis")]
+pub use oauth::{LoginStart, DeviceLoginStart, DevicePollOutcome};
+
+#[cfg(feature = "auth-redis")]
+pub mod session_store;
+#[cfg(feature = "auth-redis")]
+pub use session_store::SessionStore;
 
 #[cfg(feature = "auth-apikey")]
 pub mod apikey;
 
 #[cfg(feature = "auth-apikey")]
-pub use apikey::ApiKeyAuthService;
+pub use apikey::{ApiKeyAuthService, ApiKeyGrant};
+
+#[cfg(feature = "auth-jwt")]
+pub mod jwt;
+
+#[cfg(feature = "auth-jwt")]
+pub use jwt::JwtAuthProvider;
+
+pub mod middleware;
 
 /// Generic authentication data that different auth providers can return
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AuthData {
     pub user_id: String,
     pub metadata: serde_json::Value,
+    /// Scopes granted to this credential. An authenticated caller is not
+    /// automatically authorized for every action - tools must check this
+    /// against whatever scope(s) the requested operation requires.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+impl AuthData {
+    /// Check whether the caller was granted `scope`.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+
+    /// Reject with a distinct "authenticated but not authorized" error if the
+    /// caller lacks `scope`, so callers can tell this apart from a failed
+    /// `authenticate()` and the tool can skip running the action entirely.
+    pub fn require_scope(&self, scope: &str) -> Result<(), ServerError> {
+        if self.has_scope(scope) {
+            Ok(())
+        } else {
+            Err(ServerError::Forbidden(format!(
+                "user '{}' is missing required scope '{}'",
+                self.user_id, scope
+            )))
+        }
+    }
 }
 
-/// Trait for authentication providers
+/// Trait for authentication providers. Implementations are stored behind a
+/// `dyn AuthProvider` so the active provider can be selected at runtime from
+/// config; don't add a `Clone` supertrait here or that stops being possible.
 #[async_trait]
-pub trait AuthProvider: Clone + Send + Sync + 'static {
+pub trait AuthProvider: Send + Sync + 'static {
     /// Authenticate a credential and return user data
     async fn authenticate(&self, credential: &str) -> Result<AuthData, ServerError>;
     
@@ -31,6 +81,14 @@ pub trait AuthProvider: Clone + Send + Sync + 'static {
     fn validate_credential_format(&self, credential: &str) -> Result<(), ServerError> {
         Ok(())
     }
+
+    /// Whether `middleware::authenticate` should bother extracting and
+    /// checking a credential at all. `NoOpAuthProvider` overrides this to
+    /// `false` so a default (`auth.provider = "none"`) deployment isn't
+    /// locked out of its own unauthenticated HTTP transport.
+    fn requires_credential(&self) -> bool {
+        true
+    }
 }
 
 /// A no-op auth provider for when authentication is disabled
@@ -42,4 +100,69 @@ impl AuthProvider for NoOpAuthProvider {
     async fn authenticate(&self, _credential: &str) -> Result<AuthData, ServerError> {
         Err(ServerError::InvalidSession("Authentication is disabled".to_string()))
     }
+
+    fn requires_credential(&self) -> bool {
+        false
+    }
+}
+
+/// Build the active `AuthProvider` from `config.auth.provider`, falling back
+/// to `NoOpAuthProvider` (and logging why) if the selected provider can't be
+/// constructed from the rest of config.
+pub fn build_provider(config: &Config) -> Arc<dyn AuthProvider> {
+    match config.auth.provider {
+        AuthProviderKind::None => Arc::new(NoOpAuthProvider),
+
+        #[cfg(feature = "auth-apikey")]
+        AuthProviderKind::Apikey => match ApiKeyAuthService::from_env() {
+            Ok(service) => Arc::new(service),
+            Err(e) => {
+                tracing::error!("Failed to load API_KEYS for apikey auth provider: {}", e);
+                Arc::new(NoOpAuthProvider)
+            }
+        },
+
+        #[cfg(feature = "auth-redis")]
+        AuthProviderKind::Redis => {
+            let redis_config = config.redis.as_ref();
+            let url = redis_config.map(|r| r.url.as_str()).unwrap_or("redis://localhost:6379");
+            let oauth_providers = redis_config.map(|r| r.oauth_providers.clone()).unwrap_or_default();
+            let refresh_skew_secs = redis_config.map(|r| r.refresh_skew_secs).unwrap_or(60);
+
+            match RedisAuthService::with_oauth_providers(url, oauth_providers) {
+                Ok(service) => Arc::new(service.with_refresh_skew(refresh_skew_secs)),
+                Err(e) => {
+                    tracing::error!("Failed to initialize redis auth provider: {}", e);
+                    Arc::new(NoOpAuthProvider)
+                }
+            }
+        }
+
+        #[cfg(feature = "auth-jwt")]
+        AuthProviderKind::Jwt => match &config.jwt {
+            Some(jwt_config) => match build_jwt_provider(jwt_config) {
+                Ok(provider) => Arc::new(provider),
+                Err(e) => {
+                    tracing::error!("Failed to initialize jwt auth provider: {}", e);
+                    Arc::new(NoOpAuthProvider)
+                }
+            },
+            None => {
+                tracing::error!("auth.provider = \"jwt\" but no [jwt] config section is present");
+                Arc::new(NoOpAuthProvider)
+            }
+        },
+    }
+}
+
+#[cfg(feature = "auth-jwt")]
+fn build_jwt_provider(jwt_config: &crate::config::JwtConfig) -> Result<JwtAuthProvider, ServerError> {
+    if let Some(secret) = &jwt_config.secret {
+        Ok(JwtAuthProvider::from_hs256_secret(secret, jwt_config.issuer.as_deref(), jwt_config.audience.as_deref()))
+    } else if let Some(path) = &jwt_config.public_key_path {
+        let pem = std::fs::read(path)?;
+        JwtAuthProvider::from_rsa_pem(&pem, jwt_config.issuer.as_deref(), jwt_config.audience.as_deref())
+    } else {
+        Err(ServerError::InvalidInput("jwt auth provider requires either `secret` or `public_key_path`".to_string()))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,449 @@
+use chrono::Utc;
+use rand::Rng;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::config::OAuthProviderConfig;
+use crate::error::ServerError;
+
+use super::redis::{OAuthTokenData, RedisAuthService, SessionData, TokenResponse};
+
+/// How long a `begin_login` state/verifier pair survives in Redis before
+/// `complete_login` must have redeemed it.
+const PKCE_STATE_TTL_SECS: u64 = 600;
+/// Length of the generated PKCE `code_verifier`, within the 43-128 char range RFC 7636 allows.
+const CODE_VERIFIER_LEN: usize = 64;
+/// How long a freshly minted session stays valid.
+const SESSION_TTL: chrono::Duration = chrono::Duration::days(30);
+/// `interval` fallback when a device authorization response omits it (RFC 8628 recommends 5s).
+const DEFAULT_DEVICE_POLL_INTERVAL_SECS: u64 = 5;
+/// How much `poll_device_login` backs off the polling interval on `slow_down`.
+const SLOW_DOWN_INCREMENT_SECS: u64 = 5;
+
+/// Authorization URL and CSRF `state` to redirect the user's browser to.
+#[derive(Debug, Serialize)]
+pub struct LoginStart {
+    pub auth_url: String,
+    pub state: String,
+}
+
+/// PKCE verifier and bookkeeping stashed in Redis between `begin_login` and
+/// `complete_login`, keyed by the `state` CSRF token.
+#[derive(Serialize, Deserialize)]
+struct PendingLogin {
+    code_verifier: String,
+    provider: String,
+    created_at: String,
+}
+
+/// `user_code`/`device_code` and polling parameters for the user to complete
+/// authorization on another device.
+#[derive(Debug, Serialize)]
+pub struct DeviceLoginStart {
+    pub user_code: String,
+    pub verification_uri: String,
+    pub device_code: String,
+    pub interval: u64,
+    pub expires_in: u64,
+}
+
+/// Outcome of a single `poll_device_login` call. `Pending` means the caller
+/// should wait `interval` seconds (the latest-known interval, bumped on
+/// `slow_down`) and poll again; `Complete` carries the freshly minted session.
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DevicePollOutcome {
+    Pending { interval: u64 },
+    Complete { session_id: String },
+}
+
+/// `device_code` bookkeeping stashed in Redis between `begin_device_login`
+/// and a successful `poll_device_login`, keyed by the `device_code` itself
+/// and TTL'd to the provider's advertised `expires_in`.
+#[derive(Serialize, Deserialize)]
+struct PendingDeviceLogin {
+    provider: String,
+    interval: u64,
+}
+
+/// RFC 8628 device authorization endpoint response.
+#[derive(Deserialize)]
+struct DeviceAuthorizationResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    #[serde(default)]
+    verification_uri_complete: Option<String>,
+    expires_in: u64,
+    #[serde(default)]
+    interval: Option<u64>,
+}
+
+/// What the device token endpoint told us this poll, per RFC 8628 section 3.5.
+enum DeviceTokenOutcome {
+    Pending,
+    SlowDown,
+    Complete(TokenResponse),
+}
+
+/// Best-effort OAuth2 userinfo response - providers disagree on field names
+/// (Google uses `sub`, GitHub uses a numeric `id` and `login`), so the fields
+/// likely to be present are aliased rather than modeled per-provider.
+#[derive(Deserialize)]
+struct UserInfoResponse {
+    #[serde(alias = "id")]
+    sub: Option<serde_json::Value>,
+    email: Option<String>,
+    #[serde(alias = "login")]
+    name: Option<String>,
+}
+
+fn random_token(len: usize) -> String {
+    rand::rng()
+        .sample_iter(&rand::distr::Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// RFC 4648 base64url encoding without padding, as PKCE's `code_challenge` requires.
+fn base64url_nopad(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0] as u32;
+        let b1 = *chunk.get(1).unwrap_or(&0) as u32;
+        let b2 = *chunk.get(2).unwrap_or(&0) as u32;
+        let n = (b0 << 16) | (b1 << 8) | b2;
+
+        out.push(ALPHABET[((n >> 18) & 0x3f) as usize] as char);
+        out.push(ALPHABET[((n >> 12) & 0x3f) as usize] as char);
+        if chunk.len() > 1 {
+            out.push(ALPHABET[((n >> 6) & 0x3f) as usize] as char);
+        }
+        if chunk.len() > 2 {
+            out.push(ALPHABET[(n & 0x3f) as usize] as char);
+        }
+    }
+
+    out
+}
+
+fn code_challenge(code_verifier: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(code_verifier.as_bytes());
+    base64url_nopad(&hasher.finalize())
+}
+
+impl RedisAuthService {
+    /// Begin a PKCE authorization-code login: generates a `code_verifier` and
+    /// CSRF `state`, stashes the verifier in Redis under `state` with a short
+    /// TTL, and returns the provider's authorization URL to redirect to.
+    pub async fn begin_login(&self, provider: &str) -> Result<LoginStart, ServerError> {
+        let provider_config = self.provider_config(provider)?;
+
+        let code_verifier = random_token(CODE_VERIFIER_LEN);
+        let state = random_token(32);
+
+        let pending = PendingLogin {
+            code_verifier: code_verifier.clone(),
+            provider: provider.to_string(),
+            created_at: Utc::now().to_rfc3339(),
+        };
+        let pending_json = serde_json::to_string(&pending)
+            .map_err(|e| ServerError::InvalidSession(format!("Failed to serialize login state: {}", e)))?;
+
+        let mut conn = self.connection().await?;
+        conn.set_ex::<_, _, ()>(format!("oauth_pkce:{}", state), pending_json, PKCE_STATE_TTL_SECS)
+            .await
+            .map_err(|e| ServerError::Redis(format!("Failed to store login state: {}", e)))?;
+
+        let mut auth_url = reqwest::Url::parse(&provider_config.authorize_url)
+            .map_err(|e| ServerError::InvalidInput(format!("Invalid authorize_url: {}", e)))?;
+        {
+            let mut pairs = auth_url.query_pairs_mut();
+            pairs.append_pair("response_type", "code");
+            pairs.append_pair("client_id", &provider_config.client_id);
+            pairs.append_pair("code_challenge", &code_challenge(&code_verifier));
+            pairs.append_pair("code_challenge_method", "S256");
+            pairs.append_pair("state", &state);
+            if let Some(redirect_uri) = provider_config.redirect_uri.as_deref() {
+                pairs.append_pair("redirect_uri", redirect_uri);
+            }
+            if !provider_config.scopes.is_empty() {
+                pairs.append_pair("scope", &provider_config.scopes.join(" "));
+            }
+        }
+
+        Ok(LoginStart { auth_url: auth_url.to_string(), state })
+    }
+
+    /// Complete a PKCE login: redeems `state` for its stashed verifier
+    /// (rejecting unknown/expired state, which blocks CSRF and replay),
+    /// exchanges the code, fetches the user's profile, and mints a fresh
+    /// session id under which tools can resolve the linked OAuth token.
+    pub async fn complete_login(&self, provider: &str, code: &str, state: &str) -> Result<String, ServerError> {
+        let mut conn = self.connection().await?;
+        let pkce_key = format!("oauth_pkce:{}", state);
+
+        let pending_json: Option<String> = conn
+            .get(&pkce_key)
+            .await
+            .map_err(|e| ServerError::Redis(format!("Failed to look up login state: {}", e)))?;
+        let pending_json = pending_json
+            .ok_or_else(|| ServerError::InvalidSession("Unknown or expired login state".to_string()))?;
+
+        // Single-use: delete immediately so a replayed callback can't redeem it twice.
+        let _: Result<(), _> = conn.del(&pkce_key).await;
+
+        let pending: PendingLogin = serde_json::from_str(&pending_json)
+            .map_err(|e| ServerError::InvalidSession(format!("Invalid login state: {}", e)))?;
+
+        if pending.provider != provider {
+            return Err(ServerError::InvalidSession("Login state does not match provider".to_string()));
+        }
+
+        let provider_config = self.provider_config(provider)?;
+
+        let mut params = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("client_id", provider_config.client_id.as_str()),
+            ("client_secret", provider_config.client_secret.as_str()),
+            ("code_verifier", pending.code_verifier.as_str()),
+        ];
+        if let Some(redirect_uri) = provider_config.redirect_uri.as_deref() {
+            params.push(("redirect_uri", redirect_uri));
+        }
+
+        let token_response = self.post_token_endpoint(provider_config, &params).await?;
+        self.finish_login(provider, token_response).await
+    }
+
+    /// Begin a device authorization login (RFC 8628): registers the client
+    /// with the provider's device-authorization endpoint and stashes the
+    /// returned `device_code` in Redis - keyed by itself, TTL'd to the
+    /// advertised `expires_in` - so `poll_device_login` can find its way
+    /// back to the right provider and polling interval.
+    pub async fn begin_device_login(&self, provider: &str) -> Result<DeviceLoginStart, ServerError> {
+        let provider_config = self.provider_config(provider)?;
+        let device_authorization_url = provider_config.device_authorization_url.as_deref().ok_or_else(|| {
+            ServerError::InvalidInput("OAuth provider has no device_authorization_url configured".to_string())
+        })?;
+
+        let scope = provider_config.scopes.join(" ");
+        let mut params = vec![("client_id", provider_config.client_id.as_str())];
+        if !scope.is_empty() {
+            params.push(("scope", scope.as_str()));
+        }
+
+        let response = self.http_client()
+            .post(device_authorization_url)
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| ServerError::InvalidSession(format!("Device authorization request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ServerError::InvalidSession(format!("Device authorization request rejected: {}", body)));
+        }
+
+        let payload: DeviceAuthorizationResponse = response.json().await
+            .map_err(|e| ServerError::InvalidSession(format!("Invalid device authorization response: {}", e)))?;
+        let interval = payload.interval.unwrap_or(DEFAULT_DEVICE_POLL_INTERVAL_SECS);
+
+        let pending = PendingDeviceLogin { provider: provider.to_string(), interval };
+        let pending_json = serde_json::to_string(&pending)
+            .map_err(|e| ServerError::InvalidSession(format!("Failed to serialize device login state: {}", e)))?;
+
+        let mut conn = self.connection().await?;
+        conn.set_ex::<_, _, ()>(format!("oauth_device:{}", payload.device_code), pending_json, payload.expires_in)
+            .await
+            .map_err(|e| ServerError::Redis(format!("Failed to store device login state: {}", e)))?;
+
+        Ok(DeviceLoginStart {
+            user_code: payload.user_code,
+            verification_uri: payload.verification_uri_complete.unwrap_or(payload.verification_uri),
+            device_code: payload.device_code,
+            interval,
+            expires_in: payload.expires_in,
+        })
+    }
+
+    /// Poll the token endpoint for a pending device login. Call this no more
+    /// often than the latest-known `interval` (from `begin_device_login`, or
+    /// the bumped value returned by a prior `Pending` outcome) until it
+    /// returns `Complete` or an error.
+    pub async fn poll_device_login(&self, device_code: &str) -> Result<DevicePollOutcome, ServerError> {
+        let device_key = format!("oauth_device:{}", device_code);
+        let mut conn = self.connection().await?;
+
+        let pending_json: Option<String> = conn.get(&device_key).await
+            .map_err(|e| ServerError::Redis(format!("Failed to look up device login state: {}", e)))?;
+        let pending_json = pending_json
+            .ok_or_else(|| ServerError::InvalidSession("Unknown or expired device code".to_string()))?;
+        let mut pending: PendingDeviceLogin = serde_json::from_str(&pending_json)
+            .map_err(|e| ServerError::InvalidSession(format!("Invalid device login state: {}", e)))?;
+
+        let provider_config = self.provider_config(&pending.provider)?;
+
+        match self.request_device_token(provider_config, device_code).await? {
+            DeviceTokenOutcome::Pending => Ok(DevicePollOutcome::Pending { interval: pending.interval }),
+            DeviceTokenOutcome::SlowDown => {
+                pending.interval += SLOW_DOWN_INCREMENT_SECS;
+                let remaining_ttl: i64 = conn.ttl(&device_key).await.unwrap_or(-1);
+                let pending_json = serde_json::to_string(&pending)
+                    .map_err(|e| ServerError::InvalidSession(format!("Failed to serialize device login state: {}", e)))?;
+
+                if remaining_ttl > 0 {
+                    conn.set_ex::<_, _, ()>(&device_key, pending_json, remaining_ttl as u64)
+                        .await
+                        .map_err(|e| ServerError::Redis(format!("Failed to update device login state: {}", e)))?;
+                } else {
+                    conn.set::<_, _, ()>(&device_key, pending_json)
+                        .await
+                        .map_err(|e| ServerError::Redis(format!("Failed to update device login state: {}", e)))?;
+                }
+
+                Ok(DevicePollOutcome::Pending { interval: pending.interval })
+            }
+            DeviceTokenOutcome::Complete(token_response) => {
+                // Single-use: delete immediately so a stray extra poll after
+                // completion can't mint a second session from the same grant.
+                let _: Result<(), _> = conn.del(&device_key).await;
+
+                let session_id = self.finish_login(&pending.provider, token_response).await?;
+                Ok(DevicePollOutcome::Complete { session_id })
+            }
+        }
+    }
+
+    /// Poll the device token endpoint once, classifying the RFC 8628 section
+    /// 3.5 error codes into keep-waiting (`authorization_pending`,
+    /// `slow_down`) vs. terminal (`access_denied`, `expired_token`, anything
+    /// else) outcomes.
+    async fn request_device_token(&self, provider_config: &OAuthProviderConfig, device_code: &str) -> Result<DeviceTokenOutcome, ServerError> {
+        let params = [
+            ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+            ("device_code", device_code),
+            ("client_id", provider_config.client_id.as_str()),
+            ("client_secret", provider_config.client_secret.as_str()),
+        ];
+
+        let response = self.http_client()
+            .post(&provider_config.token_url)
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| ServerError::InvalidSession(format!("Device token request failed: {}", e)))?;
+
+        // Unlike the other grants, device-grant error responses are
+        // meaningful JSON (`{"error": "authorization_pending", ...}`) rather
+        // than opaque bodies to discard, so the status code alone can't tell
+        // us whether to keep polling - the body has to be parsed either way.
+        let body: serde_json::Value = response.json().await
+            .map_err(|e| ServerError::InvalidSession(format!("Invalid device token response: {}", e)))?;
+
+        if let Some(error) = body.get("error").and_then(|v| v.as_str()) {
+            return match error {
+                "authorization_pending" => Ok(DeviceTokenOutcome::Pending),
+                "slow_down" => Ok(DeviceTokenOutcome::SlowDown),
+                "access_denied" => Err(ServerError::InvalidSession("User denied the device authorization request".to_string())),
+                "expired_token" => Err(ServerError::InvalidSession("Device code expired before authorization completed".to_string())),
+                other => Err(ServerError::InvalidSession(format!("Device token request rejected: {}", other))),
+            };
+        }
+
+        let token_response: TokenResponse = serde_json::from_value(body)
+            .map_err(|e| ServerError::InvalidSession(format!("Invalid device token response: {}", e)))?;
+        Ok(DeviceTokenOutcome::Complete(token_response))
+    }
+
+    /// Common tail of every login flow once a token response has been
+    /// obtained: fetch the user's profile, persist the OAuth token, and mint
+    /// a fresh session under which the existing session-consuming tools can
+    /// resolve it.
+    async fn finish_login(&self, provider: &str, token_response: TokenResponse) -> Result<String, ServerError> {
+        let provider_config = self.provider_config(provider)?;
+        let user_info = self.fetch_userinfo(provider_config, &token_response.access_token).await?;
+
+        let provider_user_id = match user_info.sub {
+            Some(serde_json::Value::String(s)) => s,
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+        let user_id = format!("{}:{}", provider, provider_user_id);
+        let now = Utc::now();
+
+        let token_data = OAuthTokenData {
+            user_id: user_id.clone(),
+            provider: provider.to_string(),
+            provider_user_id,
+            email: user_info.email.unwrap_or_default(),
+            display_name: user_info.name.unwrap_or_default(),
+            access_token: token_response.access_token,
+            refresh_token: token_response.refresh_token,
+            expires_at: (now + chrono::Duration::seconds(token_response.expires_in)).to_rfc3339(),
+            scopes: token_response.scope
+                .map(|s| s.split_whitespace().map(String::from).collect())
+                .unwrap_or_default(),
+            linked_at: now.to_rfc3339(),
+        };
+        self.save_oauth_token(&token_data).await?;
+
+        let session_id = Uuid::new_v4().to_string();
+        let session = SessionData {
+            session_id: session_id.clone(),
+            user_id,
+            created_at: now.to_rfc3339(),
+            expires_at: (now + SESSION_TTL).to_rfc3339(),
+            scopes: token_data.scopes.clone(),
+        };
+        let session_json = serde_json::to_string(&session)
+            .map_err(|e| ServerError::InvalidSession(format!("Failed to serialize session: {}", e)))?;
+
+        let mut conn = self.connection().await?;
+        conn.set_ex::<_, _, ()>(format!("mcp_session:{}", session_id), session_json, SESSION_TTL.num_seconds() as u64)
+            .await
+            .map_err(|e| ServerError::Redis(format!("Failed to store session: {}", e)))?;
+
+        Ok(session_id)
+    }
+
+    async fn fetch_userinfo(&self, provider_config: &OAuthProviderConfig, access_token: &str) -> Result<UserInfoResponse, ServerError> {
+        let userinfo_url = provider_config.userinfo_url.as_deref()
+            .ok_or_else(|| ServerError::InvalidInput("OAuth provider has no userinfo_url configured".to_string()))?;
+
+        let response = self.http_client()
+            .get(userinfo_url)
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| ServerError::InvalidSession(format!("Userinfo request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ServerError::InvalidSession(format!("Userinfo request rejected: {}", body)));
+        }
+
+        response.json::<UserInfoResponse>()
+            .await
+            .map_err(|e| ServerError::InvalidSession(format!("Invalid userinfo response: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_challenge_known_vector() {
+        // RFC 7636 appendix B example verifier/challenge pair.
+        let verifier = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+        assert_eq!(code_challenge(verifier), "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM");
+    }
+}
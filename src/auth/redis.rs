@@ -1,19 +1,36 @@
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use anyhow::Result;
+use crate::config::OAuthProviderConfig;
 use crate::error::ServerError;
 use redis::AsyncCommands;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 use async_trait::async_trait;
 use super::{AuthProvider, AuthData};
 
+/// Response body from an OAuth2 token endpoint (authorization_code or refresh_token grant).
+#[derive(Deserialize)]
+pub(super) struct TokenResponse {
+    pub(super) access_token: String,
+    #[serde(default)]
+    pub(super) refresh_token: Option<String>,
+    pub(super) expires_in: i64,
+    #[serde(default)]
+    pub(super) scope: Option<String>,
+}
+
 /// Session data structure stored in Redis
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SessionData {
     pub session_id: String,
     pub user_id: String,
     pub created_at: String,
     pub expires_at: String,
+    /// Scopes granted to this session, carried over from the OAuth token it
+    /// was minted from so `AuthProvider::authenticate` can report them.
+    #[serde(default)]
+    pub scopes: Vec<String>,
 }
 
 /// OAuth token data structure stored in Redis
@@ -36,11 +53,18 @@ pub struct OAuthTokenData {
 impl OAuthTokenData {
     /// Check if the access token is expired
     pub fn is_expired(&self) -> bool {
+        self.expires_within(chrono::Duration::zero())
+    }
+
+    /// Check if the access token is expired, or will expire within `skew` -
+    /// used to refresh proactively rather than waiting for an outbound call
+    /// to fail with a dead token.
+    pub fn expires_within(&self, skew: chrono::Duration) -> bool {
         // Parse the ISO 8601 timestamp string
         match chrono::DateTime::parse_from_rfc3339(&self.expires_at) {
             Ok(expires_at) => {
                 let now = chrono::Utc::now();
-                now >= expires_at.with_timezone(&chrono::Utc)
+                now + skew >= expires_at.with_timezone(&chrono::Utc)
             }
             Err(_) => {
                 // If we can't parse the timestamp, consider it expired for safety
@@ -56,32 +80,75 @@ impl OAuthTokenData {
     }
 }
 
+/// How long before actual expiry we proactively refresh a token, so an
+/// outbound call never races a token that's about to die mid-request.
+const DEFAULT_REFRESH_SKEW_SECS: i64 = 60;
+
 /// Redis-based authentication service for handling session resolution and OAuth tokens
 #[derive(Clone)]
 pub struct RedisAuthService {
     redis_client: redis::Client,
+    oauth_providers: HashMap<String, OAuthProviderConfig>,
+    http_client: reqwest::Client,
+    refresh_skew: chrono::Duration,
 }
 
 impl RedisAuthService {
     pub fn new(redis_url: &str) -> Result<Self> {
+        Self::with_oauth_providers(redis_url, HashMap::new())
+    }
+
+    /// Construct a service that can also exchange/refresh OAuth tokens for the given providers.
+    pub fn with_oauth_providers(redis_url: &str, oauth_providers: HashMap<String, OAuthProviderConfig>) -> Result<Self> {
         let redis_client = redis::Client::open(redis_url)?;
-        Ok(Self { redis_client })
+        Ok(Self {
+            redis_client,
+            oauth_providers,
+            http_client: reqwest::Client::new(),
+            refresh_skew: chrono::Duration::seconds(DEFAULT_REFRESH_SKEW_SECS),
+        })
+    }
+
+    /// Override the default proactive-refresh skew window.
+    pub fn with_refresh_skew(mut self, skew_secs: i64) -> Self {
+        self.refresh_skew = chrono::Duration::seconds(skew_secs);
+        self
+    }
+
+    pub(super) async fn connection(&self) -> Result<redis::aio::MultiplexedConnection, ServerError> {
+        self.redis_client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| ServerError::Redis(format!("Failed to connect to Redis: {}", e)))
+    }
+
+    pub(super) fn provider_config(&self, provider: &str) -> Result<&OAuthProviderConfig, ServerError> {
+        self.oauth_providers
+            .get(provider)
+            .ok_or_else(|| ServerError::InvalidSession(format!("Unknown OAuth provider: {}", provider)))
+    }
+
+    pub(super) fn http_client(&self) -> &reqwest::Client {
+        &self.http_client
     }
 
     /// Resolve session ID to user ID via Redis lookup
     pub async fn resolve_session(&self, session_id: &str) -> Result<String, ServerError> {
+        Ok(self.resolve_session_data(session_id).await?.user_id)
+    }
+
+    /// Resolve a session ID to its full stored `SessionData`, including the
+    /// scopes it was granted at login.
+    async fn resolve_session_data(&self, session_id: &str) -> Result<SessionData, ServerError> {
         // Validate session ID format (should be UUID4)
         if Uuid::parse_str(session_id).is_err() {
             return Err(ServerError::InvalidSession("Invalid session ID format".to_string()));
         }
 
-        let mut conn = self.redis_client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(|e| ServerError::Redis(format!("Failed to connect to Redis: {}", e)))?;
+        let mut conn = self.connection().await?;
 
         let session_key = format!("mcp_session:{}", session_id);
-        
+
         let session_json: Option<String> = conn
             .get(&session_key)
             .await
@@ -89,23 +156,17 @@ impl RedisAuthService {
 
         let session_json = session_json
             .ok_or_else(|| ServerError::InvalidSession("Session not found or expired".to_string()))?;
-        
-        // Parse the session JSON to extract user_id
-        let session_data: SessionData = serde_json::from_str(&session_json)
-            .map_err(|e| ServerError::InvalidSession(format!("Invalid session data: {}", e)))?;
-        
-        Ok(session_data.user_id)
+
+        serde_json::from_str(&session_json)
+            .map_err(|e| ServerError::InvalidSession(format!("Invalid session data: {}", e)))
     }
 
-    /// Retrieve OAuth tokens for a user
-    pub async fn get_oauth_token(&self, user_id: &str, provider: &str) -> Result<OAuthTokenData, ServerError> {
-        let mut conn = self.redis_client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(|e| ServerError::Redis(format!("Failed to connect to Redis: {}", e)))?;
+    /// Fetch the stored OAuth token for a user without checking or refreshing expiry.
+    async fn fetch_oauth_token(&self, user_id: &str, provider: &str) -> Result<OAuthTokenData, ServerError> {
+        let mut conn = self.connection().await?;
 
         let oauth_key = format!("linked_account:{}:{}", user_id, provider);
-        
+
         let token_json: Option<String> = conn
             .get(&oauth_key)
             .await
@@ -114,16 +175,183 @@ impl RedisAuthService {
         let token_json = token_json
             .ok_or_else(|| ServerError::InvalidSession("OAuth token not found".to_string()))?;
 
-        let token_data: OAuthTokenData = serde_json::from_str(&token_json)
-            .map_err(|e| ServerError::InvalidSession(format!("Invalid OAuth token data: {}", e)))?;
+        serde_json::from_str(&token_json)
+            .map_err(|e| ServerError::InvalidSession(format!("Invalid OAuth token data: {}", e)))
+    }
+
+    pub(super) async fn save_oauth_token(&self, token_data: &OAuthTokenData) -> Result<(), ServerError> {
+        let mut conn = self.connection().await?;
+
+        let oauth_key = format!("linked_account:{}:{}", token_data.user_id, token_data.provider);
+        let json = serde_json::to_string(token_data)
+            .map_err(|e| ServerError::InvalidSession(format!("Failed to serialize OAuth token: {}", e)))?;
+
+        conn.set::<_, _, ()>(&oauth_key, json)
+            .await
+            .map_err(|e| ServerError::Redis(format!("Failed to store OAuth token: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Drop a linked account's stored token - used when a refresh comes back
+    /// `invalid_grant`, since the refresh token is unrecoverable at that point.
+    async fn delete_oauth_token(&self, user_id: &str, provider: &str) -> Result<(), ServerError> {
+        let mut conn = self.connection().await?;
+        let oauth_key = format!("linked_account:{}:{}", user_id, provider);
+
+        conn.del::<_, ()>(&oauth_key)
+            .await
+            .map_err(|e| ServerError::Redis(format!("Failed to delete OAuth token: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Retrieve OAuth tokens for a user, transparently refreshing them if
+    /// expired or within the configured skew window of expiring.
+    pub async fn get_oauth_token(&self, user_id: &str, provider: &str) -> Result<OAuthTokenData, ServerError> {
+        let token_data = self.fetch_oauth_token(user_id, provider).await?;
 
-        if token_data.is_expired() {
-            return Err(ServerError::InvalidSession("OAuth token expired".to_string()));
+        if token_data.expires_within(self.refresh_skew) {
+            return self.refresh_oauth_token(token_data).await;
         }
 
         Ok(token_data)
     }
 
+    pub(super) async fn post_token_endpoint(&self, provider_config: &OAuthProviderConfig, params: &[(&str, &str)]) -> Result<TokenResponse, ServerError> {
+        let response = self.http_client
+            .post(&provider_config.token_url)
+            .form(params)
+            .send()
+            .await
+            .map_err(|e| ServerError::InvalidSession(format!("OAuth token request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ServerError::InvalidSession(format!("OAuth provider rejected the request: {}", body)));
+        }
+
+        response.json::<TokenResponse>()
+            .await
+            .map_err(|e| ServerError::InvalidSession(format!("Invalid OAuth token response: {}", e)))
+    }
+
+    /// Exchange an authorization code (optionally with a PKCE verifier) for the
+    /// user's first OAuth token, and persist it under the given user.
+    pub async fn exchange_code(
+        &self,
+        user_id: &str,
+        provider: &str,
+        code: &str,
+        code_verifier: Option<&str>,
+    ) -> Result<OAuthTokenData, ServerError> {
+        let provider_config = self.provider_config(provider)?;
+
+        let mut params = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("client_id", provider_config.client_id.as_str()),
+            ("client_secret", provider_config.client_secret.as_str()),
+        ];
+        if let Some(redirect_uri) = provider_config.redirect_uri.as_deref() {
+            params.push(("redirect_uri", redirect_uri));
+        }
+        if let Some(verifier) = code_verifier {
+            params.push(("code_verifier", verifier));
+        }
+
+        let token_response = self.post_token_endpoint(provider_config, &params).await?;
+        let now = chrono::Utc::now();
+
+        let token_data = OAuthTokenData {
+            user_id: user_id.to_string(),
+            provider: provider.to_string(),
+            provider_user_id: String::new(),
+            email: String::new(),
+            display_name: String::new(),
+            access_token: token_response.access_token,
+            refresh_token: token_response.refresh_token,
+            expires_at: (now + chrono::Duration::seconds(token_response.expires_in)).to_rfc3339(),
+            scopes: token_response.scope
+                .map(|s| s.split_whitespace().map(String::from).collect())
+                .unwrap_or_default(),
+            linked_at: now.to_rfc3339(),
+        };
+
+        self.save_oauth_token(&token_data).await?;
+        Ok(token_data)
+    }
+
+    /// Refresh an expired token via its stored refresh_token, guarding against
+    /// two concurrent callers both spending the same refresh token.
+    async fn refresh_oauth_token(&self, token_data: OAuthTokenData) -> Result<OAuthTokenData, ServerError> {
+        let refresh_token = token_data.refresh_token.clone().ok_or_else(|| {
+            ServerError::InvalidSession("OAuth token expired and no refresh token is available".to_string())
+        })?;
+
+        let lock_key = format!("oauth_refresh_lock:{}:{}", token_data.user_id, token_data.provider);
+        let mut conn = self.connection().await?;
+
+        let acquired: bool = conn.set_nx(&lock_key, "1")
+            .await
+            .map_err(|e| ServerError::Redis(format!("Failed to acquire refresh lock: {}", e)))?;
+
+        if !acquired {
+            // Another call is already refreshing this token; wait briefly and re-read
+            // rather than spending the refresh token twice.
+            tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+            let current = self.fetch_oauth_token(&token_data.user_id, &token_data.provider).await?;
+            if !current.expires_within(self.refresh_skew) {
+                return Ok(current);
+            }
+            return Err(ServerError::InvalidSession("OAuth token refresh already in progress".to_string()));
+        }
+        let _: Result<(), _> = conn.expire(&lock_key, 10).await;
+
+        let result = self.do_refresh(&token_data, &refresh_token).await;
+        let _: Result<(), _> = conn.del(&lock_key).await;
+        result
+    }
+
+    async fn do_refresh(&self, token_data: &OAuthTokenData, refresh_token: &str) -> Result<OAuthTokenData, ServerError> {
+        let provider_config = self.provider_config(&token_data.provider)?;
+
+        let params = [
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", provider_config.client_id.as_str()),
+            ("client_secret", provider_config.client_secret.as_str()),
+        ];
+
+        let token_response = match self.post_token_endpoint(provider_config, &params).await {
+            Ok(response) => response,
+            Err(e) => {
+                // A revoked/expired refresh token is unrecoverable - the provider
+                // won't accept it again, so drop the linked account rather than
+                // letting every subsequent call re-attempt the same dead refresh.
+                let message = e.to_string();
+                if message.contains("invalid_grant") {
+                    self.delete_oauth_token(&token_data.user_id, &token_data.provider).await?;
+                    return Err(ServerError::InvalidSession(format!(
+                        "OAuth refresh token for provider {} was rejected (invalid_grant); re-authentication required",
+                        token_data.provider
+                    )));
+                }
+                return Err(e);
+            }
+        };
+
+        let refreshed = OAuthTokenData {
+            access_token: token_response.access_token,
+            refresh_token: token_response.refresh_token.or_else(|| Some(refresh_token.to_string())),
+            expires_at: (chrono::Utc::now() + chrono::Duration::seconds(token_response.expires_in)).to_rfc3339(),
+            ..token_data.clone()
+        };
+
+        self.save_oauth_token(&refreshed).await?;
+        Ok(refreshed)
+    }
+
     /// Complete authentication flow: session_id -> user_id -> oauth_token
     pub async fn authenticate(&self, session_id: &str, provider: &str) -> Result<OAuthTokenData, ServerError> {
         let user_id = self.resolve_session(session_id).await?;
@@ -142,14 +370,15 @@ impl RedisAuthService {
 impl AuthProvider for RedisAuthService {
     async fn authenticate(&self, credential: &str) -> Result<AuthData, ServerError> {
         // For Redis auth, the credential is the session ID
-        let user_id = self.resolve_session(credential).await?;
-        
+        let session = self.resolve_session_data(credential).await?;
+
         Ok(AuthData {
-            user_id,
+            user_id: session.user_id,
             metadata: serde_json::json!({
                 "session_id": credential,
                 "auth_type": "redis_session"
             }),
+            scopes: session.scopes,
         })
     }
     
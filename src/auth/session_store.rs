@@ -0,0 +1,90 @@
+use deadpool_redis::{Config, Pool, Runtime};
+use redis::AsyncCommands;
+use uuid::Uuid;
+
+use crate::error::ServerError;
+
+/// Key for a single session-scoped named variable - distinct from the
+/// `mcp_session:{id}` session-data key and `linked_account:*` OAuth keys.
+fn variable_key(session_id: &str, name: &str) -> String {
+    format!("session_var:{}:{}", session_id, name)
+}
+
+/// Pooled Redis-backed store for per-session named variables (e.g. a user
+/// saving `$hp` so it can be referenced later from a dice expression).
+/// Backed by a connection pool rather than `RedisAuthService::connection`'s
+/// fresh-multiplexed-connection-per-call, since opening a new Redis
+/// connection on every tool call is a real latency and file-descriptor cost
+/// under concurrent MCP clients.
+#[derive(Clone)]
+pub struct SessionStore {
+    pool: Pool,
+}
+
+impl SessionStore {
+    pub fn new(redis_url: &str) -> Result<Self, ServerError> {
+        let pool = Config::from_url(redis_url)
+            .create_pool(Some(Runtime::Tokio1))
+            .map_err(|e| ServerError::Redis(format!("Failed to create Redis pool: {}", e)))?;
+
+        Ok(Self { pool })
+    }
+
+    async fn connection(&self) -> Result<deadpool_redis::Connection, ServerError> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| ServerError::Redis(format!("Failed to acquire Redis connection: {}", e)))
+    }
+
+    /// `get`/`set`/`delete` all key off an existing session, so a caller
+    /// can't squat arbitrary variable keys under a session ID that was
+    /// never issued by `RedisAuthService`.
+    async fn ensure_session_exists(&self, session_id: &str) -> Result<(), ServerError> {
+        if Uuid::parse_str(session_id).is_err() {
+            return Err(ServerError::InvalidSession("Invalid session ID format".to_string()));
+        }
+
+        let mut conn = self.connection().await?;
+        let exists: bool = conn
+            .exists(format!("mcp_session:{}", session_id))
+            .await
+            .map_err(|e| ServerError::Redis(format!("Failed to check session: {}", e)))?;
+
+        if !exists {
+            return Err(ServerError::InvalidSession("Session not found or expired".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Save `name` = `value` for `session_id`.
+    pub async fn set(&self, session_id: &str, name: &str, value: &str) -> Result<(), ServerError> {
+        self.ensure_session_exists(session_id).await?;
+
+        let mut conn = self.connection().await?;
+        conn.set::<_, _, ()>(variable_key(session_id, name), value)
+            .await
+            .map_err(|e| ServerError::Redis(format!("Failed to set session variable '{}': {}", name, e)))
+    }
+
+    /// Look up `name` for `session_id`, returning `None` if it was never set.
+    pub async fn get(&self, session_id: &str, name: &str) -> Result<Option<String>, ServerError> {
+        self.ensure_session_exists(session_id).await?;
+
+        let mut conn = self.connection().await?;
+        conn.get(variable_key(session_id, name))
+            .await
+            .map_err(|e| ServerError::Redis(format!("Failed to get session variable '{}': {}", name, e)))
+    }
+
+    /// Remove `name` for `session_id`, if it was set.
+    pub async fn delete(&self, session_id: &str, name: &str) -> Result<(), ServerError> {
+        self.ensure_session_exists(session_id).await?;
+
+        let mut conn = self.connection().await?;
+        conn.del::<_, ()>(variable_key(session_id, name))
+            .await
+            .map_err(|e| ServerError::Redis(format!("Failed to delete session variable '{}': {}", name, e)))
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
+use rand::Rng;
+
+use crate::config::{Config, TransportType};
+
+#[derive(Parser)]
+#[command(name = env!("CARGO_PKG_NAME"), version, about = "A Model Context Protocol server")]
+pub struct Cli {
+  #[command(subcommand)]
+  pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+  /// Start the MCP server (the default when no subcommand is given)
+  Serve {
+    /// Path to a config.toml file, overriding the default discovery
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Override the configured transport ("stdio" or "http-streaming")
+    #[arg(long)]
+    transport: Option<String>,
+    /// Override the HTTP streaming port
+    #[arg(long)]
+    port: Option<u16>,
+  },
+  /// Load and fully deserialize a config file without starting any transport
+  ValidateConfig {
+    /// Path to a config.toml file, overriding the default discovery
+    #[arg(long)]
+    config: Option<PathBuf>,
+  },
+  /// Generate a random API key for pasting into the API_KEYS env var
+  GenApikey {
+    /// User ID to associate with the generated key
+    #[arg(long, default_value = "user")]
+    user: String,
+  },
+}
+
+impl Cli {
+  pub async fn run() -> Result<()> {
+    let cli = Cli::parse();
+
+    match cli.command.unwrap_or(Command::Serve { config: None, transport: None, port: None }) {
+      Command::Serve { config, transport, port } => serve(config, transport, port).await,
+      Command::ValidateConfig { config } => validate_config(config),
+      Command::GenApikey { user } => gen_apikey(&user),
+    }
+  }
+}
+
+async fn serve(config_path: Option<PathBuf>, transport: Option<String>, port: Option<u16>) -> Result<()> {
+  let mut config = Config::load_from(config_path.as_deref())?;
+  apply_overrides(&mut config, transport, port)?;
+
+  #[cfg(feature = "admin")]
+  let (_guard, reload_handle) = crate::telemetry::init_with_reload(&config.telemetry)?;
+  #[cfg(not(feature = "admin"))]
+  let _guard = crate::telemetry::init(&config.telemetry)?;
+
+  tracing::info!("Starting {} v{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+
+  #[cfg(feature = "admin")]
+  let server = crate::Server::new(config, reload_handle).await?;
+  #[cfg(not(feature = "admin"))]
+  let server = crate::Server::new(config).await?;
+
+  server.run().await
+}
+
+fn validate_config(config_path: Option<PathBuf>) -> Result<()> {
+  match Config::load_from(config_path.as_deref()) {
+    Ok(config) => {
+      println!("Config OK");
+      println!("  server.name:      {}", config.server.name);
+      println!("  server.transport: {:?}", config.server.transport);
+      println!("  telemetry.level:  {}", config.telemetry.level);
+      println!("  telemetry.format: {:?}", config.telemetry.format);
+      Ok(())
+    }
+    Err(e) => {
+      eprintln!("Config is invalid: {}", e);
+      std::process::exit(1);
+    }
+  }
+}
+
+/// Apply `--transport`/`--port` overrides on top of whatever was loaded from
+/// file or environment; these take precedence over both.
+fn apply_overrides(config: &mut Config, transport: Option<String>, port: Option<u16>) -> Result<()> {
+  if transport.is_none() && port.is_none() {
+    return Ok(());
+  }
+
+  let (current_port, current_tls) = match &config.server.transport {
+    TransportType::HttpStreaming { port, tls } => (Some(*port), tls.clone()),
+    TransportType::Stdio => (None, None),
+  };
+
+  let resolved_transport = transport.as_deref().unwrap_or(match &config.server.transport {
+    TransportType::Stdio => "stdio",
+    TransportType::HttpStreaming { .. } => "http-streaming",
+  });
+
+  config.server.transport = match resolved_transport {
+    "stdio" => TransportType::Stdio,
+    "http-streaming" => TransportType::HttpStreaming {
+      port: port.or(current_port).unwrap_or(3000),
+      tls: current_tls,
+    },
+    other => bail!("Unknown transport override: {} (expected \"stdio\" or \"http-streaming\")", other),
+  };
+
+  Ok(())
+}
+
+fn gen_apikey(user: &str) -> Result<()> {
+  let key: String = rand::rng()
+    .sample_iter(&rand::distr::Alphanumeric)
+    .take(32)
+    .map(char::from)
+    .collect();
+
+  println!("{}:{}", key, user);
+
+  #[cfg(feature = "auth-apikey")]
+  {
+    use sha2::{Digest, Sha256};
+
+    let salt: [u8; 16] = rand::random();
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(key.as_bytes());
+    let digest = hasher.finalize();
+
+    println!("stored hash: salt={} digest={}", hex_encode(&salt), hex_encode(&digest));
+  }
+
+  Ok(())
+}
+
+#[cfg(feature = "auth-apikey")]
+fn hex_encode(bytes: &[u8]) -> String {
+  bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
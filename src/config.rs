@@ -1,15 +1,62 @@
 use serde::Deserialize;
 use config::{Config as ConfigBuilder, ConfigError, File};
 use std::path::Path;
+#[cfg(feature = "admin")]
+use std::path::PathBuf;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
   pub server: ServerConfig,
   pub telemetry: TelemetryConfig,
+  #[serde(default)]
+  pub auth: AuthConfig,
   #[cfg(feature = "auth")]
   pub redis: Option<RedisConfig>,
+  #[cfg(feature = "auth-jwt")]
+  pub jwt: Option<JwtConfig>,
   #[cfg(feature = "database")]
   pub database: Option<DatabaseConfig>,
+  #[cfg(feature = "http-client")]
+  pub http_client: Option<HttpClientConfig>,
+  #[cfg(feature = "admin")]
+  pub admin: Option<AdminConfig>,
+  /// Path this config was loaded from, if any - not part of the file/env
+  /// config itself, stamped by `load_from` so `AdminExampleTool::reload_config` can
+  /// re-read the same source later. `#[serde(skip)]` keeps it out of the
+  /// deserialized config (and out of `MCP_ADMIN__CONFIG_PATH` confusion).
+  #[cfg(feature = "admin")]
+  #[serde(skip)]
+  pub config_path: Option<PathBuf>,
+}
+
+/// Credential guarding the privileged tools in `tools::admin_example`. Set
+/// `token` via the `MCP_ADMIN__TOKEN` env var rather than committing it to
+/// config.toml, the same way Redis/OAuth secrets are handled.
+#[cfg(feature = "admin")]
+#[derive(Debug, Deserialize, Clone)]
+pub struct AdminConfig {
+  pub token: String,
+}
+
+/// Selects which `AuthProvider` guards the HTTP transport. Defaults to `none`
+/// (`NoOpAuthProvider`) so unauthenticated deployments are unaffected.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct AuthConfig {
+  #[serde(default)]
+  pub provider: AuthProviderKind,
+}
+
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthProviderKind {
+  #[default]
+  None,
+  #[cfg(feature = "auth-apikey")]
+  Apikey,
+  #[cfg(feature = "auth-redis")]
+  Redis,
+  #[cfg(feature = "auth-jwt")]
+  Jwt,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -23,7 +70,27 @@ pub struct ServerConfig {
 pub enum TransportType {
   Stdio,
   #[serde(rename = "http-streaming")]
-  HttpStreaming { port: u16 },
+  HttpStreaming {
+    port: u16,
+    #[serde(default)]
+    tls: Option<TlsConfig>,
+  },
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TlsConfig {
+  /// PEM-encoded certificate path. When omitted (together with `key_path`),
+  /// a self-signed certificate is generated at startup.
+  pub cert_path: Option<String>,
+  /// PEM-encoded private key path.
+  pub key_path: Option<String>,
+  /// Subject alternative names for the self-signed certificate.
+  #[serde(default = "default_subject_alt_names")]
+  pub subject_alt_names: Vec<String>,
+}
+
+fn default_subject_alt_names() -> Vec<String> {
+  vec!["localhost".to_string()]
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -44,6 +111,48 @@ pub enum LogFormat {
 #[derive(Debug, Deserialize, Clone)]
 pub struct RedisConfig {
   pub url: String,
+  /// OAuth client configuration, keyed by provider name (e.g. "google", "github").
+  #[serde(default)]
+  pub oauth_providers: std::collections::HashMap<String, OAuthProviderConfig>,
+  /// How many seconds before actual expiry to proactively refresh an OAuth
+  /// access token, so outbound calls never race a token about to die mid-request.
+  #[serde(default = "default_refresh_skew_secs")]
+  pub refresh_skew_secs: i64,
+}
+
+fn default_refresh_skew_secs() -> i64 {
+  60
+}
+
+#[cfg(feature = "auth")]
+#[derive(Debug, Deserialize, Clone)]
+pub struct OAuthProviderConfig {
+  pub authorize_url: String,
+  pub token_url: String,
+  pub client_id: String,
+  pub client_secret: String,
+  #[serde(default)]
+  pub redirect_uri: Option<String>,
+  #[serde(default)]
+  pub scopes: Vec<String>,
+  /// Profile endpoint queried after token exchange to learn the user's
+  /// provider_user_id/email/display_name. Required for `oauth::complete_login`.
+  #[serde(default)]
+  pub userinfo_url: Option<String>,
+  /// RFC 8628 device authorization endpoint. Required for `oauth::begin_device_login`.
+  #[serde(default)]
+  pub device_authorization_url: Option<String>,
+}
+
+#[cfg(feature = "auth-jwt")]
+#[derive(Debug, Deserialize, Clone)]
+pub struct JwtConfig {
+  /// HS256 shared secret. Mutually exclusive with `public_key_path`.
+  pub secret: Option<String>,
+  /// Path to a PEM-encoded RS256 public key. Mutually exclusive with `secret`.
+  pub public_key_path: Option<String>,
+  pub issuer: Option<String>,
+  pub audience: Option<String>,
 }
 
 #[cfg(feature = "database")]
@@ -53,67 +162,113 @@ pub struct DatabaseConfig {
   pub max_connections: u32,
 }
 
+#[cfg(feature = "http-client")]
+#[derive(Debug, Deserialize, Clone)]
+pub struct HttpClientConfig {
+  /// How many times to retry a transient failure before giving up.
+  #[serde(default = "default_max_retries")]
+  pub max_retries: u32,
+  /// Base delay (ms) for exponential backoff, before jitter is applied.
+  #[serde(default = "default_base_delay_ms")]
+  pub base_delay_ms: u64,
+  /// Upper bound (ms) on the backoff delay between retries.
+  #[serde(default = "default_max_delay_ms")]
+  pub max_delay_ms: u64,
+}
+
+#[cfg(feature = "http-client")]
+fn default_max_retries() -> u32 {
+  3
+}
+
+#[cfg(feature = "http-client")]
+fn default_base_delay_ms() -> u64 {
+  200
+}
+
+#[cfg(feature = "http-client")]
+fn default_max_delay_ms() -> u64 {
+  10_000
+}
+
 impl Config {
-  pub fn load() -> Result<Self, ConfigError> {
-    // Check for config files
-    let config_path = if Path::new("config.toml").exists() {
-      Some("config.toml")
+  /// Merge a `.env` file into the process environment before config is built,
+  /// so secrets like `REDIS_URL`/`DATABASE_URL` can live outside the repo.
+  /// The file is selected by `ENV` (`.env.production` vs plain `.env`,
+  /// defaulting to `.env`); variables already set in the environment win.
+  fn merge_dotenv() {
+    let dotenv_path = match std::env::var("ENV") {
+      Ok(env_name) if !env_name.is_empty() => format!(".env.{}", env_name),
+      _ => ".env".to_string(),
+    };
+
+    match dotenvy::from_filename(&dotenv_path) {
+      Ok(_) => tracing::info!("Merged environment from {}", dotenv_path),
+      Err(e) => tracing::debug!("No {} file merged: {}", dotenv_path, e),
+    }
+  }
+
+  /// Base TOML layer used when no config file is present on disk, so the
+  /// required fields still have somewhere to come from before env overrides apply.
+  fn default_source() -> File<config::FileSourceString, config::FileFormat> {
+    let port = std::env::var("PORT").ok().and_then(|p| p.parse::<u16>().ok()).unwrap_or(3000);
+
+    let toml = format!(
+      "[server]\nname = \"{name}\"\n\n[server.transport.http-streaming]\nport = {port}\n\n[telemetry]\nlevel = \"info\"\nformat = \"pretty\"\n",
+      name = env!("CARGO_PKG_NAME"),
+      port = port,
+    );
+
+    File::from_str(&toml, config::FileFormat::Toml)
+  }
+
+  /// Like `load`, but loads an explicit config file path instead of the
+  /// default discovery (`config.toml`, `/config.toml`) when one is given.
+  pub fn load_from(path: Option<&Path>) -> Result<Self, ConfigError> {
+    Self::merge_dotenv();
+
+    let mut builder = ConfigBuilder::builder();
+
+    builder = if let Some(path) = path {
+      tracing::info!("Loading config from: {}", path.display());
+      builder.add_source(File::from(path))
+    } else if Path::new("config.toml").exists() {
+      tracing::info!("Loading config from: config.toml");
+      builder.add_source(File::with_name("config.toml"))
     } else if Path::new("/config.toml").exists() {
-      Some("/config.toml")
+      tracing::info!("Loading config from: /config.toml");
+      builder.add_source(File::with_name("/config.toml"))
     } else {
-      None
+      tracing::info!("No config file found, using defaults + environment");
+      builder.add_source(Self::default_source())
     };
 
-    // If we have a config file, use it
-    if let Some(path) = config_path {
-      tracing::info!("Loading config from: {}", path);
-      let config = ConfigBuilder::builder()
-        .add_source(File::with_name(path))
-        .build()?;
-      
-      let mut config: Config = config.try_deserialize()?;
-      
-      // Force logging to file for stdio transport
-      if matches!(config.server.transport, TransportType::Stdio) && config.telemetry.file.is_none() {
-        config.telemetry.file = Some(format!("/tmp/{}.log", env!("CARGO_PKG_NAME")));
-      }
-      
-      return Ok(config);
+    // Environment variables override individual fields on top of the file,
+    // e.g. MCP_SERVER__TRANSPORT or MCP_TELEMETRY__LEVEL.
+    builder = builder.add_source(
+      config::Environment::with_prefix("MCP")
+        .prefix_separator("_")
+        .separator("__")
+        .try_parsing(true),
+    );
+
+    let built = builder.build()?;
+    let mut config: Config = built.try_deserialize()?;
+
+    #[cfg(feature = "admin")]
+    {
+      config.config_path = path.map(Path::to_path_buf);
+    }
+
+    // Force logging to file for stdio transport
+    if matches!(config.server.transport, TransportType::Stdio) && config.telemetry.file.is_none() {
+      config.telemetry.file = Some(format!("/tmp/{}.log", env!("CARGO_PKG_NAME")));
     }
 
-    // No config file - build from environment variables
-    tracing::info!("No config file found, building from environment variables");
-    
-    // Get port from Railway's PORT env var
-    let port = std::env::var("PORT")
-      .unwrap_or_else(|_| "3000".to_string())
-      .parse::<u16>()
-      .unwrap_or(3000);
-    
-    // Build config manually from env vars
-    Ok(Config {
-      server: ServerConfig {
-        name: env!("CARGO_PKG_NAME").to_string(),
-        transport: TransportType::HttpStreaming { port },
-      },
-      telemetry: TelemetryConfig {
-        level: std::env::var("MCP_TELEMETRY_LEVEL").unwrap_or_else(|_| "info".to_string()),
-        format: match std::env::var("MCP_TELEMETRY_FORMAT").as_deref() {
-          Ok("json") => LogFormat::Json,
-          _ => LogFormat::Pretty,
-        },
-        file: None,
-      },
-      #[cfg(feature = "auth")]
-      redis: std::env::var("MCP_REDIS_URL")
-        .or_else(|_| std::env::var("REDIS_URL"))
-        .ok()
-        .map(|url| RedisConfig { url }),
-      #[cfg(feature = "database")]
-      database: std::env::var("DATABASE_URL").ok().map(|url| DatabaseConfig {
-        url,
-        max_connections: 10,
-      }),
-    })
+    Ok(config)
+  }
+
+  pub fn load() -> Result<Self, ConfigError> {
+    Self::load_from(None)
   }
 }
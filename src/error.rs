@@ -1,3 +1,8 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rmcp::ErrorData as McpError;
+use rmcp::model::ErrorCode;
+use serde_json::json;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -21,10 +26,17 @@ pub enum ServerError {
   #[error("IO error: {0}")]
   Io(#[from] std::io::Error),
 
+  #[error("TLS error: {0}")]
+  Tls(String),
+
   #[cfg(feature = "auth")]
   #[error("Authentication error: {0}")]
   InvalidSession(String),
 
+  #[cfg(feature = "auth")]
+  #[error("Forbidden: {0}")]
+  Forbidden(String),
+
   #[cfg(feature = "auth")]
   #[error("Redis error: {0}")]
   Redis(String),
@@ -33,3 +45,156 @@ pub enum ServerError {
   #[error("HTTP client error: {0}")]
   HttpClient(String),
 }
+
+// JSON-RPC reserves -32000..=-32099 for implementation-defined server
+// errors; MCP itself claims -32002 for "resource not found"
+// (`ErrorCode::RESOURCE_NOT_FOUND`), so the rest of this crate's codes are
+// chosen to avoid both that and the standard -32600..=-32700 range.
+const DATABASE_ERROR_CODE: ErrorCode = ErrorCode(-32001);
+const REDIS_ERROR_CODE: ErrorCode = ErrorCode(-32003);
+const INVALID_SESSION_CODE: ErrorCode = ErrorCode(-32004);
+const FORBIDDEN_CODE: ErrorCode = ErrorCode(-32005);
+const HTTP_CLIENT_ERROR_CODE: ErrorCode = ErrorCode(-32006);
+
+/// Process-lifetime counts of how many times each `ServerError` variant has
+/// been converted into an `McpError` and returned to a caller. Exposed via
+/// the admin tool's `error_counts` (see `tools::admin_example`) so operators
+/// can see recent failure patterns without standing up a separate metrics
+/// pipeline.
+pub struct ErrorCounts {
+  config: AtomicU64,
+  tool_execution: AtomicU64,
+  resource_not_found: AtomicU64,
+  invalid_input: AtomicU64,
+  #[cfg(feature = "database")]
+  database: AtomicU64,
+  io: AtomicU64,
+  tls: AtomicU64,
+  #[cfg(feature = "auth")]
+  invalid_session: AtomicU64,
+  #[cfg(feature = "auth")]
+  forbidden: AtomicU64,
+  #[cfg(feature = "auth")]
+  redis: AtomicU64,
+  #[cfg(feature = "http-client")]
+  http_client: AtomicU64,
+}
+
+impl ErrorCounts {
+  const fn new() -> Self {
+    Self {
+      config: AtomicU64::new(0),
+      tool_execution: AtomicU64::new(0),
+      resource_not_found: AtomicU64::new(0),
+      invalid_input: AtomicU64::new(0),
+      #[cfg(feature = "database")]
+      database: AtomicU64::new(0),
+      io: AtomicU64::new(0),
+      tls: AtomicU64::new(0),
+      #[cfg(feature = "auth")]
+      invalid_session: AtomicU64::new(0),
+      #[cfg(feature = "auth")]
+      forbidden: AtomicU64::new(0),
+      #[cfg(feature = "auth")]
+      redis: AtomicU64::new(0),
+      #[cfg(feature = "http-client")]
+      http_client: AtomicU64::new(0),
+    }
+  }
+
+  fn record(&self, err: &ServerError) {
+    let counter = match err {
+      ServerError::Config(_) => &self.config,
+      ServerError::ToolExecution(_) => &self.tool_execution,
+      ServerError::ResourceNotFound(_) => &self.resource_not_found,
+      ServerError::InvalidInput(_) => &self.invalid_input,
+      #[cfg(feature = "database")]
+      ServerError::Database(_) => &self.database,
+      ServerError::Io(_) => &self.io,
+      ServerError::Tls(_) => &self.tls,
+      #[cfg(feature = "auth")]
+      ServerError::InvalidSession(_) => &self.invalid_session,
+      #[cfg(feature = "auth")]
+      ServerError::Forbidden(_) => &self.forbidden,
+      #[cfg(feature = "auth")]
+      ServerError::Redis(_) => &self.redis,
+      #[cfg(feature = "http-client")]
+      ServerError::HttpClient(_) => &self.http_client,
+    };
+
+    counter.fetch_add(1, Ordering::Relaxed);
+  }
+
+  /// Snapshot every counter into a JSON object keyed by variant name.
+  pub fn snapshot(&self) -> serde_json::Value {
+    let mut counts = serde_json::Map::new();
+
+    counts.insert("config".to_string(), self.config.load(Ordering::Relaxed).into());
+    counts.insert("tool_execution".to_string(), self.tool_execution.load(Ordering::Relaxed).into());
+    counts.insert("resource_not_found".to_string(), self.resource_not_found.load(Ordering::Relaxed).into());
+    counts.insert("invalid_input".to_string(), self.invalid_input.load(Ordering::Relaxed).into());
+    #[cfg(feature = "database")]
+    counts.insert("database".to_string(), self.database.load(Ordering::Relaxed).into());
+    counts.insert("io".to_string(), self.io.load(Ordering::Relaxed).into());
+    counts.insert("tls".to_string(), self.tls.load(Ordering::Relaxed).into());
+    #[cfg(feature = "auth")]
+    counts.insert("invalid_session".to_string(), self.invalid_session.load(Ordering::Relaxed).into());
+    #[cfg(feature = "auth")]
+    counts.insert("forbidden".to_string(), self.forbidden.load(Ordering::Relaxed).into());
+    #[cfg(feature = "auth")]
+    counts.insert("redis".to_string(), self.redis.load(Ordering::Relaxed).into());
+    #[cfg(feature = "http-client")]
+    counts.insert("http_client".to_string(), self.http_client.load(Ordering::Relaxed).into());
+
+    serde_json::Value::Object(counts)
+  }
+}
+
+/// Global counters updated by every `ServerError` -> `McpError` conversion.
+pub static ERROR_COUNTS: ErrorCounts = ErrorCounts::new();
+
+/// Map each `ServerError` variant to a deterministic JSON-RPC error code and
+/// a machine-readable `data` payload (including a `retryable` hint for
+/// transient failures), so MCP clients can branch on error kind instead of
+/// pattern-matching display strings.
+impl From<ServerError> for McpError {
+  fn from(err: ServerError) -> Self {
+    ERROR_COUNTS.record(&err);
+    let message = err.to_string();
+
+    match err {
+      ServerError::InvalidInput(_) => McpError::invalid_params(message, Some(json!({"retryable": false}))),
+
+      ServerError::ResourceNotFound(_) => McpError::resource_not_found(message, Some(json!({"retryable": false}))),
+
+      ServerError::ToolExecution(_) | ServerError::Io(_) | ServerError::Config(_) | ServerError::Tls(_) => {
+        McpError::internal_error(message, Some(json!({"retryable": false})))
+      }
+
+      #[cfg(feature = "database")]
+      ServerError::Database(_) => {
+        McpError::new(DATABASE_ERROR_CODE, message, Some(json!({"retryable": true})))
+      }
+
+      #[cfg(feature = "auth")]
+      ServerError::Redis(_) => {
+        McpError::new(REDIS_ERROR_CODE, message, Some(json!({"retryable": true})))
+      }
+
+      #[cfg(feature = "auth")]
+      ServerError::InvalidSession(_) => {
+        McpError::new(INVALID_SESSION_CODE, message, Some(json!({"retryable": false})))
+      }
+
+      #[cfg(feature = "auth")]
+      ServerError::Forbidden(_) => {
+        McpError::new(FORBIDDEN_CODE, message, Some(json!({"retryable": false})))
+      }
+
+      #[cfg(feature = "http-client")]
+      ServerError::HttpClient(_) => {
+        McpError::new(HTTP_CLIENT_ERROR_CODE, message, Some(json!({"retryable": true})))
+      }
+    }
+  }
+}
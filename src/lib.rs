@@ -1,8 +1,11 @@
+pub mod auth;
+pub mod cli;
 pub mod config;
 pub mod error;
 pub mod tools;
 pub mod state;
 pub mod telemetry;
+pub mod tls;
 
 use std::future::Future;
 use std::net::SocketAddr;
@@ -17,13 +20,19 @@ use tower::Service;
 
 use crate::config::Config;
 use crate::state::ServerState;
-use crate::tools::dice_example::{DiceToolExample, RollRequestExample};
+use crate::tools::dice_example::{DiceToolExample, RollRequestExample, RollExprRequest, SetVariableRequest};
 
 #[derive(Clone)]
 pub struct Server {
   config: Config,
   #[allow(dead_code)]
   state: ServerState,
+  dice: DiceToolExample,
+  /// Privileged operability tools, mounted as a separate `/admin/*` HTTP
+  /// surface in `run()` (not part of `tool_router`) when `[admin]` is
+  /// configured - see `tools::admin_example`.
+  #[cfg(feature = "admin")]
+  admin: Option<crate::tools::admin_example::AdminExampleTool>,
   tool_router: ToolRouter<Self>,
 }
 
@@ -31,31 +40,85 @@ pub struct Server {
 impl Server {
   // Replace with your own tools, these are for example
   #[tool(description = "Roll dice with specified number of sides")]
-  pub async fn roll(&self, Parameters(RollRequestExample{count, sides}): Parameters<RollRequestExample>) -> Result<CallToolResult, McpError>{
-    let req = RollRequestExample{count, sides};
-    DiceToolExample.roll(req).await
+  pub async fn roll(&self, Parameters(req): Parameters<RollRequestExample>) -> Result<CallToolResult, McpError>{
+    if let Some(auth_data) = auth::middleware::current() {
+      tracing::debug!(user_id = %auth_data.user_id, "roll called by authenticated user");
+    }
+
+    self.dice.roll(req).await
   }
 
   #[tool(description = "Roll a standard six-sided die (d6)")]
   pub async fn roll_d6(&self) -> Result<CallToolResult, McpError>{
-    self.roll(Parameters(RollRequestExample{count: 1, sides: 6})).await
+    self.roll(Parameters(RollRequestExample{count: 1, sides: 6, system: None, target: None})).await
   }
 
   #[tool(description = "Roll a standard twenty-sided die (d20)")]
   pub async fn roll_d20(&self) -> Result<CallToolResult, McpError>{
-    self.roll(Parameters(RollRequestExample{count: 1, sides: 20})).await
+    self.roll(Parameters(RollRequestExample{count: 1, sides: 20, system: None, target: None})).await
+  }
+
+  #[tool(description = "Roll a dice-notation expression, e.g. '2d6+1d8+3', 'd20-2', '4d6kh3' (keep highest 3 of 4), or '2d6!' (exploding)")]
+  pub async fn roll_expr(&self, Parameters(req): Parameters<RollExprRequest>) -> Result<CallToolResult, McpError>{
+    if let Some(auth_data) = auth::middleware::current() {
+      tracing::debug!(user_id = %auth_data.user_id, "roll_expr called by authenticated user");
+    }
+
+    self.dice.roll_expr(req).await
+  }
+
+  #[tool(description = "Save a named variable (e.g. '$hp') under a session, so later roll_expr calls can reference it as $name")]
+  pub async fn set_variable(&self, Parameters(req): Parameters<SetVariableRequest>) -> Result<CallToolResult, McpError>{
+    self.dice.set_variable(req).await
   }
 }
 
 impl Server {
+  #[cfg(feature = "admin")]
+  pub async fn new(config: Config, reload_handle: telemetry::LevelReloadHandle) -> anyhow::Result<Self> {
+    tracing::info!("Initializing MCP Server");
+    tracing::info!("Loading server state and tools...");
+
+    let state = ServerState::new(&config).await?;
+
+    #[cfg(feature = "auth-redis")]
+    let dice = match &state.session_store {
+      Some(session_store) => DiceToolExample::new().with_session_store(session_store.clone()),
+      None => DiceToolExample::new(),
+    };
+    #[cfg(not(feature = "auth-redis"))]
+    let dice = DiceToolExample::new();
+
+    let admin = config.admin.as_ref().map(|admin_config| {
+      tools::admin_example::AdminExampleTool::new(
+        admin_config.token.clone(),
+        Self::tool_router(),
+        reload_handle,
+        config.config_path.clone(),
+      )
+    });
+
+    tracing::info!("Server initialization complete");
+    Ok(Self { config, state, dice, admin, tool_router: Self::tool_router(), })
+  }
+
+  #[cfg(not(feature = "admin"))]
   pub async fn new(config: Config) -> anyhow::Result<Self> {
     tracing::info!("Initializing MCP Server");
     tracing::info!("Loading server state and tools...");
-    
+
     let state = ServerState::new(&config).await?;
-    
+
+    #[cfg(feature = "auth-redis")]
+    let dice = match &state.session_store {
+      Some(session_store) => DiceToolExample::new().with_session_store(session_store.clone()),
+      None => DiceToolExample::new(),
+    };
+    #[cfg(not(feature = "auth-redis"))]
+    let dice = DiceToolExample::new();
+
     tracing::info!("Server initialization complete");
-    Ok(Self { config, state, tool_router: Self::tool_router(), })
+    Ok(Self { config, state, dice, tool_router: Self::tool_router(), })
   }
 
   pub async fn run(self) -> anyhow::Result<()> {
@@ -82,36 +145,48 @@ impl Server {
           }
         }
       }
-      config::TransportType::HttpStreaming { port } => {
+      config::TransportType::HttpStreaming { port, tls } => {
+        let scheme = if tls.is_some() { "https" } else { "http" };
         tracing::info!("MCP Server ready!");
         tracing::info!("Transport: HTTP Streaming (using rmcp StreamableHttpService)");
-        tracing::info!("Server URL: http://localhost:{}", port);
-        
+        tracing::info!("Server URL: {}://localhost:{}", scheme, port);
+
         let addr: SocketAddr = format!("0.0.0.0:{}", port).parse().unwrap();
-        
+
         // Create the rmcp StreamableHttpService
         use std::sync::Arc;
         use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
-        
+
         let session_manager = Arc::new(LocalSessionManager::default());
         let config = StreamableHttpServerConfig::default();
-        
+        let auth_provider = self.state.auth_provider.clone();
+        #[cfg(feature = "admin")]
+        let admin_tool = self.admin.clone();
+
         let service = StreamableHttpService::new(
           move || Ok(self.clone()),
           session_manager,
           config,
         );
-        
+
         // Create HTTP server using axum
         let app = axum::Router::new()
           .fallback_service(tower::service_fn(move |req| {
             let mut service = service.clone();
             async move { service.call(req).await }
-          }));
-        
-        let listener = tokio::net::TcpListener::bind(addr).await?;
-        let server = axum::serve(listener, app);
-        
+          }))
+          .layer(axum::middleware::from_fn(move |req, next| {
+            let provider = auth_provider.clone();
+            async move { auth::middleware::authenticate(provider, req, next).await }
+          }))
+          .layer(telemetry::AccessLog);
+
+        #[cfg(feature = "admin")]
+        let app = match admin_tool {
+          Some(admin) => app.nest("/admin", admin_router(admin)),
+          None => app,
+        };
+
         // Set up graceful shutdown using the same pattern as STDIO
         let shutdown = tokio::spawn(async move {
           if let Err(e) = tokio::signal::ctrl_c().await {
@@ -120,15 +195,39 @@ impl Server {
           tracing::info!("Shutdown signal received");
         });
 
-        tokio::select! {
-          result = server => {
-            match result {
-              Ok(_) => tracing::info!("HTTP server stopped normally"),
-              Err(e) => tracing::error!("HTTP server stopped with error: {}", e),
+        if let Some(tls_config) = tls {
+          let rustls_config = tls::load_or_generate(&tls_config).await?;
+          let server = axum_server::bind_rustls(addr, rustls_config)
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>());
+
+          tokio::select! {
+            result = server => {
+              match result {
+                Ok(_) => tracing::info!("HTTPS server stopped normally"),
+                Err(e) => tracing::error!("HTTPS server stopped with error: {}", e),
+              }
+            }
+            _ = shutdown => {
+              tracing::info!("Shutting down gracefully");
             }
           }
-          _ = shutdown => {
-            tracing::info!("Shutting down gracefully");
+        } else {
+          let listener = tokio::net::TcpListener::bind(addr).await?;
+          let server = axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+          );
+
+          tokio::select! {
+            result = server => {
+              match result {
+                Ok(_) => tracing::info!("HTTP server stopped normally"),
+                Err(e) => tracing::error!("HTTP server stopped with error: {}", e),
+              }
+            }
+            _ = shutdown => {
+              tracing::info!("Shutting down gracefully");
+            }
           }
         }
       }
@@ -138,6 +237,62 @@ impl Server {
   }
 }
 
+/// Nested router mounted at `/admin` when `[admin]` is configured. Kept as
+/// plain HTTP handlers (not `#[tool]` methods on `Server`) so this surface
+/// never shows up in `tools/list` for regular MCP clients. It is only served
+/// by the HTTP streaming transport; a STDIO deployment has no admin surface.
+#[cfg(feature = "admin")]
+fn admin_router(admin: tools::admin_example::AdminExampleTool) -> axum::Router {
+  use axum::routing::post;
+  use tools::admin_example::{AdminExampleTool, AdminRequest, ToolSchemaRequest};
+
+  async fn respond(result: Result<CallToolResult, McpError>) -> axum::response::Response {
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+
+    match result {
+      Ok(result) if result.is_error != Some(true) => (StatusCode::OK, axum::Json(result)).into_response(),
+      Ok(result) => (StatusCode::BAD_REQUEST, axum::Json(result)).into_response(),
+      Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+  }
+
+  async fn list_tools(
+    axum::extract::State(admin): axum::extract::State<AdminExampleTool>,
+    axum::Json(req): axum::Json<AdminRequest>,
+  ) -> axum::response::Response {
+    respond(admin.list_tools(req).await).await
+  }
+
+  async fn tool_schema(
+    axum::extract::State(admin): axum::extract::State<AdminExampleTool>,
+    axum::Json(req): axum::Json<ToolSchemaRequest>,
+  ) -> axum::response::Response {
+    respond(admin.tool_schema(req).await).await
+  }
+
+  async fn error_counts(
+    axum::extract::State(admin): axum::extract::State<AdminExampleTool>,
+    axum::Json(req): axum::Json<AdminRequest>,
+  ) -> axum::response::Response {
+    respond(admin.error_counts(req).await).await
+  }
+
+  async fn reload_config(
+    axum::extract::State(admin): axum::extract::State<AdminExampleTool>,
+    axum::Json(req): axum::Json<AdminRequest>,
+  ) -> axum::response::Response {
+    respond(admin.reload_config(req).await).await
+  }
+
+  axum::Router::new()
+    .route("/list_tools", post(list_tools))
+    .route("/tool_schema", post(tool_schema))
+    .route("/error_counts", post(error_counts))
+    .route("/reload_config", post(reload_config))
+    .with_state(admin)
+}
+
 #[tool_handler]
 impl ServerHandler for Server {
   fn get_info(&self) -> ServerInfo {
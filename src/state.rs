@@ -1,16 +1,18 @@
+use std::sync::Arc;
 use std::time::Instant;
+use crate::auth::AuthProvider;
 use crate::config::Config;
 use anyhow::Result;
 
-#[cfg(feature = "database")]
-use std::sync::Arc;
-
 #[derive(Clone)]
 pub struct ServerState {
   start_time: Instant,
+  pub auth_provider: Arc<dyn AuthProvider>,
   // Add your shared state here
   #[cfg(feature = "database")]
   pub db: Option<Arc<sqlx::SqlitePool>>,
+  #[cfg(feature = "auth-redis")]
+  pub session_store: Option<crate::auth::SessionStore>,
 }
 
 impl ServerState {
@@ -18,12 +20,19 @@ impl ServerState {
     #[cfg(feature = "database")]
     let mut state = Self {
       start_time: Instant::now(),
+      auth_provider: crate::auth::build_provider(_config),
       db: None,
+      #[cfg(feature = "auth-redis")]
+      session_store: None,
     };
 
     #[cfg(not(feature = "database"))]
-    let state = Self {
+    #[allow(unused_mut)]
+    let mut state = Self {
       start_time: Instant::now(),
+      auth_provider: crate::auth::build_provider(_config),
+      #[cfg(feature = "auth-redis")]
+      session_store: None,
     };
 
     #[cfg(feature = "database")]
@@ -32,6 +41,14 @@ impl ServerState {
       state.db = Some(Arc::new(pool));
     }
 
+    #[cfg(feature = "auth-redis")]
+    if let Some(redis_config) = &_config.redis {
+      match crate::auth::SessionStore::new(&redis_config.url) {
+        Ok(store) => state.session_store = Some(store),
+        Err(e) => tracing::error!("Failed to initialize session variable store: {}", e),
+      }
+    }
+
     Ok(state)
   }
 
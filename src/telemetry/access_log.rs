@@ -0,0 +1,121 @@
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use axum::extract::{ConnectInfo, Request};
+use axum::response::Response;
+use tower::{Layer, Service};
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Tower layer that opens a tracing span per HTTP request and logs access
+/// details (status, elapsed time) when the request completes or is dropped.
+#[derive(Clone, Default)]
+pub struct AccessLog;
+
+impl<S> Layer<S> for AccessLog {
+  type Service = AccessLogService<S>;
+
+  fn layer(&self, inner: S) -> Self::Service {
+    AccessLogService { inner }
+  }
+}
+
+#[derive(Clone)]
+pub struct AccessLogService<S> {
+  inner: S,
+}
+
+impl<S> Service<Request> for AccessLogService<S>
+where
+  S: Service<Request, Response = Response> + Clone + Send + 'static,
+  S::Future: Send + 'static,
+{
+  type Response = Response;
+  type Error = S::Error;
+  type Future = Pin<Box<dyn Future<Output = Result<Response, S::Error>> + Send>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self.inner.poll_ready(cx)
+  }
+
+  fn call(&mut self, req: Request) -> Self::Future {
+    let request_id = Uuid::new_v4();
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    let remote_addr = req
+      .extensions()
+      .get::<ConnectInfo<SocketAddr>>()
+      .map(|ConnectInfo(addr)| addr.to_string())
+      .unwrap_or_else(|| "unknown".to_string());
+
+    let span = tracing::info_span!(
+      "http_request",
+      %request_id,
+      %method,
+      %path,
+      %remote_addr,
+    );
+
+    // Clone-then-swap so `self.inner` stays ready for the next call while
+    // this request drives its own clone to completion.
+    let clone = self.inner.clone();
+    let mut inner = std::mem::replace(&mut self.inner, clone);
+
+    let start = Instant::now();
+
+    let fut = async move {
+      let mut guard = AccessLogGuard::new(start, request_id, method, path, remote_addr);
+      let response = inner.call(req).await?;
+      guard.status = Some(response.status().as_u16());
+      Ok(response)
+    };
+
+    Box::pin(fut.instrument(span))
+  }
+}
+
+/// Records the access-log line on drop, so a cancelled (not just completed)
+/// request still gets an entry with its elapsed time.
+struct AccessLogGuard {
+  start: Instant,
+  request_id: Uuid,
+  method: axum::http::Method,
+  path: String,
+  remote_addr: String,
+  status: Option<u16>,
+}
+
+impl AccessLogGuard {
+  fn new(start: Instant, request_id: Uuid, method: axum::http::Method, path: String, remote_addr: String) -> Self {
+    Self { start, request_id, method, path, remote_addr, status: None }
+  }
+}
+
+impl Drop for AccessLogGuard {
+  fn drop(&mut self) {
+    let elapsed_ms = self.start.elapsed().as_millis() as u64;
+
+    match self.status {
+      Some(status) => tracing::info!(
+        request_id = %self.request_id,
+        method = %self.method,
+        path = %self.path,
+        remote_addr = %self.remote_addr,
+        status,
+        elapsed_ms,
+        "request completed"
+      ),
+      None => tracing::warn!(
+        request_id = %self.request_id,
+        method = %self.method,
+        path = %self.path,
+        remote_addr = %self.remote_addr,
+        elapsed_ms,
+        "request cancelled before completion"
+      ),
+    }
+  }
+}
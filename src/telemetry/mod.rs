@@ -0,0 +1,68 @@
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, reload, EnvFilter, Registry};
+use crate::config::{TelemetryConfig, LogFormat};
+use anyhow::Result;
+
+pub mod access_log;
+pub use access_log::AccessLog;
+
+/// Handle that lets `tools::admin_example::AdminExampleTool::reload_config`
+/// swap the live tracing level filter without restarting the process. Only
+/// the filter is reloadable - output format/destination are wired once into
+/// the `fmt` layer at `init`.
+#[cfg(feature = "admin")]
+pub type LevelReloadHandle = reload::Handle<EnvFilter, Registry>;
+
+pub fn init(config: &TelemetryConfig) -> Result<tracing_appender::non_blocking::WorkerGuard> {
+  let (guard, _handle) = init_inner(config)?;
+  Ok(guard)
+}
+
+/// Like `init`, but also returns a handle that can later swap the level
+/// filter - for a binary that wants to wire up `AdminExampleTool`.
+#[cfg(feature = "admin")]
+pub fn init_with_reload(config: &TelemetryConfig) -> Result<(tracing_appender::non_blocking::WorkerGuard, LevelReloadHandle)> {
+  init_inner(config)
+}
+
+fn init_inner(config: &TelemetryConfig) -> Result<(tracing_appender::non_blocking::WorkerGuard, reload::Handle<EnvFilter, Registry>)> {
+  let env_filter = EnvFilter::try_from_default_env()
+    .unwrap_or_else(|_| EnvFilter::new(&config.level));
+
+  let (filter_layer, reload_handle) = reload::Layer::new(env_filter);
+
+  let (non_blocking, guard) = if let Some(file_path) = &config.file {
+    let file_appender = tracing_appender::rolling::daily("logs", file_path);
+    tracing_appender::non_blocking(file_appender)
+  } else {
+    tracing_appender::non_blocking(std::io::stdout())
+  };
+
+  let subscriber = tracing_subscriber::registry()
+    .with(filter_layer);
+
+  match config.format {
+    LogFormat::Pretty => {
+      subscriber.with(tracing_subscriber::fmt::layer()
+        .with_writer(non_blocking)
+        .pretty())
+        .init();
+    }
+    LogFormat::Json => {
+      subscriber.with(tracing_subscriber::fmt::layer()
+        .with_writer(non_blocking)
+        .json())
+        .init();
+    }
+  }
+
+  Ok((guard, reload_handle))
+}
+
+/// Swap the live tracing level filter, e.g. in response to an admin-triggered
+/// config reload. Takes the already-resolved level from a freshly loaded
+/// `Config` rather than re-reading `RUST_LOG`/`MCP_TELEMETRY__LEVEL` itself.
+#[cfg(feature = "admin")]
+pub fn reload_level(handle: &LevelReloadHandle, level: &str) -> Result<()> {
+  handle.reload(EnvFilter::new(level))
+    .map_err(|e| anyhow::anyhow!("Failed to reload tracing level filter: {}", e))
+}
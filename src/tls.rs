@@ -0,0 +1,59 @@
+use std::io::BufReader;
+use std::sync::Arc;
+
+use axum_server::tls_rustls::RustlsConfig;
+
+use crate::config::TlsConfig;
+use crate::error::ServerError;
+
+/// Build a rustls server config from the configured cert/key PEM files, or
+/// generate a self-signed certificate when neither path is supplied.
+pub async fn load_or_generate(tls: &TlsConfig) -> Result<RustlsConfig, ServerError> {
+  let (cert_pem, key_pem) = match (&tls.cert_path, &tls.key_path) {
+    (Some(cert_path), Some(key_path)) => {
+      let cert_pem = std::fs::read(cert_path)
+        .map_err(|e| ServerError::Tls(format!("Failed to read TLS certificate at {}: {}", cert_path, e)))?;
+      let key_pem = std::fs::read(key_path)
+        .map_err(|e| ServerError::Tls(format!("Failed to read TLS private key at {}: {}", key_path, e)))?;
+      (cert_pem, key_pem)
+    }
+    (None, None) => {
+      tracing::info!("No TLS cert/key configured, generating a self-signed certificate for {:?}", tls.subject_alt_names);
+      generate_self_signed(&tls.subject_alt_names)?
+    }
+    _ => {
+      return Err(ServerError::Tls("TLS cert_path and key_path must be set together".to_string()));
+    }
+  };
+
+  let certs = rustls_pemfile::certs(&mut BufReader::new(cert_pem.as_slice()))
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| ServerError::Tls(format!("Malformed TLS certificate PEM: {}", e)))?;
+
+  let key = rustls_pemfile::private_key(&mut BufReader::new(key_pem.as_slice()))
+    .map_err(|e| ServerError::Tls(format!("Malformed TLS private key PEM: {}", e)))?
+    .ok_or_else(|| ServerError::Tls("No private key found in PEM".to_string()))?;
+
+  let server_config = rustls::ServerConfig::builder()
+    .with_no_client_auth()
+    .with_single_cert(certs, key)
+    .map_err(|e| ServerError::Tls(format!("Invalid TLS certificate/key pair: {}", e)))?;
+
+  Ok(RustlsConfig::from_config(Arc::new(server_config)))
+}
+
+fn generate_self_signed(subject_alt_names: &[String]) -> Result<(Vec<u8>, Vec<u8>), ServerError> {
+  let sans = if subject_alt_names.is_empty() {
+    vec!["localhost".to_string()]
+  } else {
+    subject_alt_names.to_vec()
+  };
+
+  let certified_key = rcgen::generate_simple_self_signed(sans)
+    .map_err(|e| ServerError::Tls(format!("Failed to generate self-signed certificate: {}", e)))?;
+
+  let cert_pem = certified_key.cert.pem();
+  let key_pem = certified_key.key_pair.serialize_pem();
+
+  Ok((cert_pem.into_bytes(), key_pem.into_bytes()))
+}
@@ -0,0 +1,191 @@
+use std::path::PathBuf;
+
+use rmcp::{ErrorData as McpError, model::{CallToolResult, Content}};
+use rmcp::handler::server::router::tool::ToolRouter;
+use serde::Deserialize;
+use schemars::JsonSchema;
+
+use crate::error::{ServerError, ERROR_COUNTS};
+use crate::telemetry::LevelReloadHandle;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AdminRequest {
+    /// Admin token configured via `[admin].token` / `MCP_ADMIN__TOKEN`.
+    pub admin_token: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ToolSchemaRequest {
+    /// Admin token configured via `[admin].token` / `MCP_ADMIN__TOKEN`.
+    pub admin_token: String,
+    /// Name of a tool registered on this server, e.g. "roll_expr".
+    pub tool_name: String,
+}
+
+/// Operability tools (tool introspection, error counters, config reload) for
+/// a running server, gated by a shared admin token rather than `AuthProvider`
+/// - these are a separate trust boundary from the user-facing tool-calling
+/// path, and must keep working the same whether or not an `auth` provider is
+/// configured at all.
+#[derive(Clone)]
+pub struct AdminExampleTool {
+    token: String,
+    tool_router: ToolRouter<crate::Server>,
+    reload_handle: LevelReloadHandle,
+    config_path: Option<PathBuf>,
+}
+
+impl AdminExampleTool {
+    pub fn new(
+        token: String,
+        tool_router: ToolRouter<crate::Server>,
+        reload_handle: LevelReloadHandle,
+        config_path: Option<PathBuf>,
+    ) -> Self {
+        Self { token, tool_router, reload_handle, config_path }
+    }
+
+    /// Reject every token other than the configured one with the same generic
+    /// "invalid token" error. Tokens of the configured length are compared in
+    /// constant time; a token of any other length is rejected immediately.
+    fn verify(&self, provided: &str) -> Result<(), McpError> {
+        if constant_time_eq(self.token.as_bytes(), provided.as_bytes()) {
+            Ok(())
+        } else {
+            Err(ServerError::InvalidInput("Invalid admin token".to_string()).into())
+        }
+    }
+
+    /// List every tool registered on this server's `ToolRouter`.
+    pub async fn list_tools(&self, req: AdminRequest) -> Result<CallToolResult, McpError> {
+        self.verify(&req.admin_token)?;
+
+        let lines: Vec<String> = self.tool_router.list_all()
+            .into_iter()
+            .map(|tool| format!("• {} - {}", tool.name, tool.description.as_deref().unwrap_or("(no description)")))
+            .collect();
+
+        Ok(CallToolResult::success(vec![Content::text(format!("Registered tools:\n{}", lines.join("\n")))]))
+    }
+
+    /// Return the JSON schema `tool_name` expects for its input.
+    pub async fn tool_schema(&self, req: ToolSchemaRequest) -> Result<CallToolResult, McpError> {
+        self.verify(&req.admin_token)?;
+
+        let tool = self.tool_router.list_all()
+            .into_iter()
+            .find(|tool| tool.name == req.tool_name)
+            .ok_or_else(|| ServerError::ResourceNotFound(format!("No tool named '{}'", req.tool_name)))?;
+
+        let schema = serde_json::to_string_pretty(&tool.input_schema)
+            .map_err(|e| ServerError::ToolExecution(format!("Failed to serialize schema for '{}': {}", req.tool_name, e)))?;
+
+        Ok(CallToolResult::success(vec![Content::text(schema)]))
+    }
+
+    /// Report how many times each `ServerError` variant has been surfaced to
+    /// a client since the process started.
+    pub async fn error_counts(&self, req: AdminRequest) -> Result<CallToolResult, McpError> {
+        self.verify(&req.admin_token)?;
+
+        let snapshot = serde_json::to_string_pretty(&ERROR_COUNTS.snapshot())
+            .map_err(|e| ServerError::ToolExecution(format!("Failed to serialize error counts: {}", e)))?;
+
+        Ok(CallToolResult::success(vec![Content::text(snapshot)]))
+    }
+
+    /// Re-read the config file this server started with and apply whatever
+    /// subset of it can safely change on a live process. Only the tracing
+    /// level filter qualifies today - transport, auth provider, and Redis/
+    /// database URLs are read once at startup and need a restart.
+    pub async fn reload_config(&self, req: AdminRequest) -> Result<CallToolResult, McpError> {
+        self.verify(&req.admin_token)?;
+
+        let new_config = crate::config::Config::load_from(self.config_path.as_deref())
+            .map_err(ServerError::Config)?;
+
+        crate::telemetry::reload_level(&self.reload_handle, &new_config.telemetry.level)
+            .map_err(|e| ServerError::ToolExecution(format!("Failed to apply reloaded log level: {}", e)))?;
+
+        let source = self.config_path.as_deref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "default discovery (config.toml, /config.toml, or built-in defaults) + environment".to_string());
+
+        let result_text = format!(
+            "Config reloaded from {}.\n\
+             • telemetry.level applied live: {}\n\
+             • server.transport, auth.provider, redis/database URLs, and TLS \
+               settings are read once at startup and need a process restart to change.",
+            source, new_config.telemetry.level,
+        );
+
+        Ok(CallToolResult::success(vec![Content::text(result_text)]))
+    }
+}
+
+/// Byte-for-byte comparison that always walks the full length of `expected`
+/// rather than returning as soon as a difference is found, so response
+/// timing can't be used to learn how many leading bytes of a guessed token
+/// were correct.
+fn constant_time_eq(expected: &[u8], provided: &[u8]) -> bool {
+    if expected.len() != provided.len() {
+        return false;
+    }
+
+    let mut diff = 0u8;
+    for (a, b) in expected.iter().zip(provided.iter()) {
+        diff |= a ^ b;
+    }
+
+    diff == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(token: &str) -> AdminExampleTool {
+        AdminExampleTool::new(
+            token.to_string(),
+            crate::Server::tool_router(),
+            // Tests never call `reload_config`, so a throwaway filter/handle
+            // pair is fine here - building a real one means duplicating
+            // `telemetry::init`'s non-blocking writer setup for no benefit.
+            tracing_subscriber::reload::Layer::new(tracing_subscriber::EnvFilter::new("info")).1,
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_rejects_wrong_token() {
+        let admin = tool("correct-token");
+
+        let result = admin.list_tools(AdminRequest { admin_token: "wrong-token".to_string() }).await;
+        assert!(result.is_err(), "Should reject an incorrect admin token");
+    }
+
+    #[tokio::test]
+    async fn test_accepts_correct_token() {
+        let admin = tool("correct-token");
+
+        let result = admin.list_tools(AdminRequest { admin_token: "correct-token".to_string() }).await;
+        assert!(result.is_ok(), "Should accept the configured admin token");
+    }
+
+    #[tokio::test]
+    async fn test_tool_schema_rejects_unknown_tool() {
+        let admin = tool("correct-token");
+
+        let result = admin.tool_schema(ToolSchemaRequest {
+            admin_token: "correct-token".to_string(),
+            tool_name: "does_not_exist".to_string(),
+        }).await;
+
+        assert!(result.is_err(), "Should reject a tool name that isn't registered");
+    }
+
+    #[test]
+    fn test_constant_time_eq_rejects_length_mismatch() {
+        assert!(!constant_time_eq(b"short", b"a-much-longer-token"));
+    }
+}
@@ -10,6 +10,11 @@ pub struct AuthenticatedRequest {
     pub credential: String,
     /// The action to perform
     pub action: String,
+    /// Scopes the caller must hold to perform `action`. An authenticated
+    /// caller missing one of these is rejected as forbidden rather than
+    /// having the action run on their behalf.
+    #[serde(default)]
+    pub required_scopes: Vec<String>,
 }
 
 /// Generic authenticated tool that works with any auth provider
@@ -56,11 +61,20 @@ impl AuthExampleTool {
                 .map_err(|e| {
                     tracing::error!("❌ Authentication failed: {}", e);
                     McpError::invalid_params(
-                        format!("Authentication failed: {}", e), 
+                        format!("Authentication failed: {}", e),
                         None
                     )
                 })?;
 
+            // Being authenticated doesn't imply being authorized for this
+            // specific action - check every scope it requires before running it.
+            for scope in &req.required_scopes {
+                if let Err(e) = auth_data.require_scope(scope) {
+                    tracing::error!("❌ {}", e);
+                    return Err(e.into());
+                }
+            }
+
             let result_text = format!(
                 "Authenticated Action:\n\
                  • User ID: {}\n\
@@ -101,36 +115,60 @@ mod tests {
     #[cfg(feature = "auth-apikey")]
     #[tokio::test]
     async fn test_with_api_key_auth() {
-        use crate::auth::ApiKeyAuthService;
+        use crate::auth::{ApiKeyAuthService, ApiKeyGrant};
         use std::collections::HashMap;
-        
+
         let mut keys = HashMap::new();
-        keys.insert("test-key".to_string(), "user123".to_string());
-        
+        keys.insert("test-key".to_string(), ApiKeyGrant::from("user123"));
+
         let auth = ApiKeyAuthService::new(keys);
         let tool = AuthExampleTool::new(auth);
-        
+
         let req = AuthenticatedRequest {
             credential: "test-key".to_string(),
             action: "test-action".to_string(),
+            required_scopes: Vec::new(),
         };
-        
+
         let result = tool.authenticated_action(req).await;
         assert!(result.is_ok());
     }
-    
+
+    #[cfg(feature = "auth-apikey")]
+    #[tokio::test]
+    async fn test_rejects_missing_scope() {
+        use crate::auth::{ApiKeyAuthService, ApiKeyGrant};
+        use std::collections::HashMap;
+
+        let mut keys = HashMap::new();
+        keys.insert("test-key".to_string(), ApiKeyGrant::new("user123", vec!["read".to_string()]));
+
+        let auth = ApiKeyAuthService::new(keys);
+        let tool = AuthExampleTool::new(auth);
+
+        let req = AuthenticatedRequest {
+            credential: "test-key".to_string(),
+            action: "delete-everything".to_string(),
+            required_scopes: vec!["admin".to_string()],
+        };
+
+        let result = tool.authenticated_action(req).await;
+        assert!(result.is_err(), "Should reject a caller missing the required scope");
+    }
+
     #[cfg(feature = "auth-redis")]
-    #[tokio::test] 
+    #[tokio::test]
     async fn test_with_redis_auth() {
         use crate::auth::RedisAuthService;
-        
+
         // This would need a real Redis instance to work
         let auth = RedisAuthService::new("redis://localhost:6379").unwrap();
         let tool = AuthExampleTool::new(auth);
-        
+
         let req = AuthenticatedRequest {
             credential: "550e8400-e29b-41d4-a716-446655440000".to_string(),
             action: "test-action".to_string(),
+            required_scopes: Vec::new(),
         };
         
         // This would fail without a real session in Redis
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use super::RollRequestExample;
+
+/// Outcome of interpreting a roll under a particular game system's rules.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemOutcome{
+  /// This roll has no system-specific meaning beyond the plain total.
+  Plain,
+  /// A named success/failure tier (e.g. `"critical_success"`, `"fumble"`),
+  /// with a human-readable explanation of why the roll landed there.
+  Tiered{tier: String, detail: String},
+}
+
+/// A pluggable tabletop rule set that turns a raw roll into a
+/// system-specific outcome. Register new systems in `build_registry` to
+/// make them selectable via `RollRequestExample::system`.
+pub trait GameSystem: Send + Sync{
+  /// Name callers select with `RollRequestExample::system`.
+  fn name(&self) -> &'static str;
+
+  /// Interpret `raw` (the dice actually rolled for `req`) under this
+  /// system's rules. Errs if `req` is missing something this system needs
+  /// (e.g. a skill/threshold distinct from `req.sides`).
+  fn evaluate(&self, req: &RollRequestExample, raw: &[u32]) -> Result<SystemOutcome, String>;
+}
+
+/// A Call of Cthulhu-style percentile roll-under check: the first die in
+/// `raw` is compared against `req.target` (the skill/threshold to roll
+/// under) - deliberately NOT `req.sides`, which is always the die actually
+/// rolled (100 for a proper percentile roll) and would make every roll
+/// trivially `<= target` if reused as the target too. A critical success
+/// happens on 1, a hard success at a fifth of the target or under, and a
+/// fumble on a failed roll of 96 or more (a 96+ at or under the target is
+/// still a success).
+pub struct CthulhuPercentile;
+
+impl GameSystem for CthulhuPercentile{
+  fn name(&self) -> &'static str{ "cthulhu" }
+
+  fn evaluate(&self, req: &RollRequestExample, raw: &[u32]) -> Result<SystemOutcome, String>{
+    let Some(&roll) = raw.first() else{
+      return Ok(SystemOutcome::Plain);
+    };
+    let target = req.target
+      .ok_or_else(|| "game system 'cthulhu' requires `target` (the skill/threshold to roll under)".to_string())?;
+
+    let tier = if roll == 1{
+      "critical_success"
+    }else if roll <= target / 5{
+      "hard_success"
+    }else if roll <= target{
+      "success"
+    }else if roll >= 96{
+      "fumble"
+    }else{
+      "failure"
+    };
+
+    Ok(SystemOutcome::Tiered{
+      tier: tier.to_string(),
+      detail: format!("rolled {} against a target of {}", roll, target),
+    })
+  }
+}
+
+/// Build the startup registry of available game systems, keyed by `name()`.
+/// Add new systems here to make them selectable without touching the tool's
+/// request/response handling.
+pub fn build_registry() -> HashMap<String, Box<dyn GameSystem>>{
+  let systems: Vec<Box<dyn GameSystem>> = vec![Box::new(CthulhuPercentile)];
+  systems.into_iter().map(|s| (s.name().to_string(), s)).collect()
+}
+
+#[cfg(test)]
+mod tests{
+  use super::*;
+
+  fn req(target: Option<u32>) -> RollRequestExample{
+    RollRequestExample{sides: 100, count: 1, system: Some("cthulhu".to_string()), target}
+  }
+
+  #[test]
+  fn test_evaluate_requires_target(){
+    let outcome = CthulhuPercentile.evaluate(&req(None), &[50]);
+    assert!(outcome.is_err(), "Should require `target` rather than falling back to `sides`");
+  }
+
+  #[test]
+  fn test_critical_success_on_a_roll_of_one(){
+    let SystemOutcome::Tiered{tier, ..} = CthulhuPercentile.evaluate(&req(Some(50)), &[1]).unwrap() else {
+      panic!("expected a tiered outcome");
+    };
+    assert_eq!(tier, "critical_success");
+  }
+
+  #[test]
+  fn test_hard_success_at_a_fifth_of_target(){
+    let SystemOutcome::Tiered{tier, ..} = CthulhuPercentile.evaluate(&req(Some(50)), &[10]).unwrap() else {
+      panic!("expected a tiered outcome");
+    };
+    assert_eq!(tier, "hard_success");
+  }
+
+  #[test]
+  fn test_success_between_hard_success_and_target(){
+    let SystemOutcome::Tiered{tier, ..} = CthulhuPercentile.evaluate(&req(Some(50)), &[40]).unwrap() else {
+      panic!("expected a tiered outcome");
+    };
+    assert_eq!(tier, "success");
+  }
+
+  #[test]
+  fn test_fumble_in_the_96_to_100_band(){
+    let SystemOutcome::Tiered{tier, ..} = CthulhuPercentile.evaluate(&req(Some(50)), &[97]).unwrap() else {
+      panic!("expected a tiered outcome");
+    };
+    assert_eq!(tier, "fumble");
+  }
+
+  #[test]
+  fn test_plain_failure_above_target_but_below_fumble_band(){
+    let SystemOutcome::Tiered{tier, ..} = CthulhuPercentile.evaluate(&req(Some(50)), &[70]).unwrap() else {
+      panic!("expected a tiered outcome");
+    };
+    assert_eq!(tier, "failure");
+  }
+}
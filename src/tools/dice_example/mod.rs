@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use rand::Rng;
+use rmcp::{ErrorData as McpError, model::{CallToolResult, Content}};
+use serde::Deserialize;
+use schemars::JsonSchema;
+
+mod parser;
+mod game_system;
+
+use parser::{EvaluatedTerm, RolledDie};
+use game_system::{GameSystem, SystemOutcome};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RollRequestExample{
+  /// Number of sides on the dice (e.g. 6 for d6, 20 for d20)
+  pub sides: u32,
+  /// Number of dice to roll
+  #[serde(default = "default_count")]
+  pub count: u32,
+  /// Optional game system (e.g. "cthulhu") to interpret this roll under that
+  /// system's success/failure rules, on top of the plain total
+  #[serde(default)]
+  pub system: Option<String>,
+  /// Skill/threshold number for percentile-style systems (e.g. "cthulhu"),
+  /// compared against the roll to decide success/failure. Distinct from
+  /// `sides`, which is always the die actually rolled.
+  #[serde(default)]
+  pub target: Option<u32>,
+}
+
+fn default_count() -> u32{1}
+
+#[derive(Clone)]
+pub struct DiceToolExample{
+  /// Game systems available to `RollRequestExample::system`, built once at
+  /// startup. Add new `GameSystem` impls in `game_system::build_registry`.
+  systems: Arc<HashMap<String, Box<dyn GameSystem>>>,
+  /// Backing store for `RollExprRequest::session_id`'s `$name` variables.
+  /// `None` when no Redis is configured - `roll_expr` then rejects any
+  /// request that references a session.
+  #[cfg(feature = "auth-redis")]
+  session_store: Option<crate::auth::SessionStore>,
+}
+
+impl DiceToolExample{
+  pub fn new() -> Self{
+    Self{
+      systems: Arc::new(game_system::build_registry()),
+      #[cfg(feature = "auth-redis")]
+      session_store: None,
+    }
+  }
+
+  #[cfg(feature = "auth-redis")]
+  pub fn with_session_store(mut self, session_store: crate::auth::SessionStore) -> Self{
+    self.session_store = Some(session_store);
+    self
+  }
+
+  pub async fn roll(&self, req: RollRequestExample) -> Result<CallToolResult, McpError>{
+    if req.sides == 0{
+      return Err(McpError::invalid_params("Dice must have at least 1 side", None));
+    }
+    if req.count == 0 || req.count > 100{
+      return Err(McpError::invalid_params("Count must be between 1 and 100", None));
+    }
+
+    let mut rng = rand::rng();
+    let rolls: Vec<u32> = (0..req.count)
+      .map(|_| rng.random_range(1..=req.sides))
+      .collect();
+
+    let total: u32 = rolls.iter().sum();
+
+    let system_outcome = match &req.system{
+      Some(name) => {
+        let system = self.systems.get(name)
+          .ok_or_else(|| McpError::invalid_params(format!("Unknown game system '{}'", name), None))?;
+        Some(system.evaluate(&req, &rolls).map_err(|e| McpError::invalid_params(e, None))?)
+      }
+      None => None,
+    };
+
+    let mut result_text = if req.count == 1{
+      format!("Rolled a d{}: {}", req.sides, rolls[0])
+    }else{
+      format!(
+        "Rolled {}d{}: {} (total: {})",
+        req.count,
+        req.sides,
+        rolls.iter().map(|r| r.to_string()).collect::<Vec<String>>().join(", "),
+        total
+      )
+    };
+
+    if let Some(SystemOutcome::Tiered{tier, detail}) = system_outcome{
+      result_text.push_str(&format!(" - {} ({})", tier, detail));
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(result_text)]))
+  }
+
+  /// Roll a dice-notation expression like "2d6+1d8+3", "d20-2", or
+  /// "4d6kh3!" (keep-highest-3 of 4, exploding). When `session_id` is set
+  /// and a session variable store is configured, `$name` references are
+  /// resolved from it first (e.g. a `$hp` saved earlier as "3d6").
+  pub async fn roll_expr(&self, req: RollExprRequest) -> Result<CallToolResult, McpError>{
+    #[cfg(feature = "auth-redis")]
+    let expr_text = match (&req.session_id, &self.session_store){
+      (Some(session_id), Some(store)) => {
+        parser::resolve_variables(&req.expr, session_id, store).await.map_err(McpError::from)?
+      }
+      (Some(_), None) => {
+        return Err(McpError::internal_error("Session variables are not configured on this server", None));
+      }
+      (None, _) => req.expr.clone(),
+    };
+    #[cfg(not(feature = "auth-redis"))]
+    let expr_text = req.expr.clone();
+
+    let expr = parser::parse(&expr_text).map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+    let evaluation = parser::evaluate(&expr).map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+
+    let breakdown = evaluation.terms
+      .iter()
+      .enumerate()
+      .map(|(i, term)| format_term(i == 0, term))
+      .collect::<Vec<String>>()
+      .join(" ");
+
+    let result_text = format!("Rolled '{}': {} = {}", req.expr, breakdown, evaluation.total);
+
+    Ok(CallToolResult::success(vec![Content::text(result_text)]))
+  }
+
+  /// Save `name` = `value` under `session_id`, so a later `roll_expr` call
+  /// can reference it as `$name` (e.g. saving `$hp` as "3d6").
+  pub async fn set_variable(&self, req: SetVariableRequest) -> Result<CallToolResult, McpError>{
+    #[cfg(feature = "auth-redis")]
+    {
+      let store = self.session_store.as_ref()
+        .ok_or_else(|| McpError::internal_error("Session variables are not configured on this server", None))?;
+
+      store.set(&req.session_id, &req.name, &req.value).await.map_err(McpError::from)?;
+
+      Ok(CallToolResult::success(vec![Content::text(
+        format!("Saved ${} = \"{}\" for session {}", req.name, req.value, req.session_id)
+      )]))
+    }
+    #[cfg(not(feature = "auth-redis"))]
+    {
+      let _ = req;
+      Err(McpError::internal_error("Session variables are not configured on this server", None))
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetVariableRequest{
+  /// Session ID (UUID4) to save this variable under.
+  pub session_id: String,
+  /// Variable name, referenced later as `$name` in a `roll_expr` expression.
+  pub name: String,
+  /// Value to store, e.g. a dice expression like "3d6" or a plain number.
+  pub value: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RollExprRequest{
+  /// Dice-notation expression, e.g. "2d6+1d8+3", "d20-2", "4d6kh3" (keep
+  /// highest 3 of 4), "4d6dl1" (drop lowest 1 of 4), or "2d6!" (exploding)
+  pub expr: String,
+  /// Session ID (UUID4) whose saved `$name` variables this expression may
+  /// reference. Required only if `expr` contains a `$name` token.
+  #[cfg(feature = "auth-redis")]
+  #[serde(default)]
+  pub session_id: Option<String>,
+}
+
+/// Render one die's roll, showing its full explosion chain when it exploded
+/// (e.g. `"6+6+2"`) or just the value when it didn't (e.g. `"3"`).
+fn format_die_chain(die: &RolledDie) -> String{
+  die.chain.iter().map(|r| r.to_string()).collect::<Vec<String>>().join("+")
+}
+
+/// Render one evaluated term as `"2d6[3, 5]"`/`"3"`, prefixed with its sign -
+/// the leading term omits a redundant "+". Dice dropped by a keep/drop
+/// modifier are parenthesized so the breakdown stays auditable.
+fn format_term(is_first: bool, term: &EvaluatedTerm) -> String{
+  let (sign, body) = match term{
+    EvaluatedTerm::Const{sign, value} => (*sign, value.to_string()),
+    EvaluatedTerm::Dice{sign, count, sides, dice} => (
+      *sign,
+      format!(
+        "{}d{}[{}]",
+        count,
+        sides,
+        dice.iter()
+          .map(|(die, kept)| if *kept{ format_die_chain(die) }else{ format!("({})", format_die_chain(die)) })
+          .collect::<Vec<String>>()
+          .join(", "),
+      ),
+    ),
+  };
+
+  match (is_first, sign < 0){
+    (true, true) => format!("-{}", body),
+    (true, false) => body,
+    (false, true) => format!("- {}", body),
+    (false, false) => format!("+ {}", body),
+  }
+}
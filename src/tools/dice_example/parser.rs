@@ -0,0 +1,471 @@
+use rand::Rng;
+
+use crate::error::ServerError;
+
+/// Maximum total dice (summed across every `DiceGroup`) a single expression may roll.
+const MAX_TOTAL_DICE: u32 = 100;
+/// Hard cap on how many times a single die can explode, so a `sides == 1`
+/// exploding die (which would always re-trigger) can't loop forever.
+const MAX_EXPLOSIONS: u32 = 50;
+
+/// Dice-notation AST: `expr := term (('+'|'-') term)*`,
+/// `term := (count)?'d'sides modifier* | integer`,
+/// `modifier := ('kh'|'kl'|'dh'|'dl') integer | '!'`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr{
+  Const(i64),
+  DiceGroup{count: u32, sides: u32, keep: Option<KeepRule>, explode: bool},
+  Add(Box<Expr>, Box<Expr>),
+  Sub(Box<Expr>, Box<Expr>),
+}
+
+/// Which dice in a group are summed, by rolled value once all are in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum KeepRule{
+  KeepHighest(u32),
+  KeepLowest(u32),
+  DropHighest(u32),
+  DropLowest(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token{
+  Number(u32),
+  Ident(String),
+  Plus,
+  Minus,
+  Bang,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ServerError>{
+  let mut tokens = Vec::new();
+  let mut chars = input.chars().peekable();
+
+  while let Some(&c) = chars.peek(){
+    match c{
+      c if c.is_whitespace() => { chars.next(); }
+      '+' => { chars.next(); tokens.push(Token::Plus); }
+      '-' => { chars.next(); tokens.push(Token::Minus); }
+      '!' => { chars.next(); tokens.push(Token::Bang); }
+      c if c.is_ascii_digit() => {
+        let mut digits = String::new();
+        while let Some(&c) = chars.peek(){
+          if c.is_ascii_digit(){
+            digits.push(c);
+            chars.next();
+          }else{
+            break;
+          }
+        }
+        let value = digits.parse::<u32>()
+          .map_err(|e| ServerError::InvalidInput(format!("Invalid number '{}': {}", digits, e)))?;
+        tokens.push(Token::Number(value));
+      }
+      c if c.is_alphabetic() => {
+        // Maximal-munch so "d" (the dice operator) and "kh"/"kl"/"dh"/"dl"
+        // (modifiers) tokenize unambiguously despite sharing a leading letter.
+        let mut ident = String::new();
+        while let Some(&c) = chars.peek(){
+          if c.is_alphabetic(){
+            ident.push(c.to_ascii_lowercase());
+            chars.next();
+          }else{
+            break;
+          }
+        }
+        tokens.push(Token::Ident(ident));
+      }
+      other => return Err(ServerError::InvalidInput(format!("Unexpected character '{}' in dice expression", other))),
+    }
+  }
+
+  Ok(tokens)
+}
+
+struct Parser{
+  tokens: Vec<Token>,
+  pos: usize,
+}
+
+impl Parser{
+  fn peek(&self) -> Option<&Token>{
+    self.tokens.get(self.pos)
+  }
+
+  fn advance(&mut self) -> Option<Token>{
+    let token = self.tokens.get(self.pos).cloned();
+    self.pos += 1;
+    token
+  }
+
+  fn expect_number(&mut self) -> Result<u32, ServerError>{
+    match self.advance(){
+      Some(Token::Number(n)) => Ok(n),
+      other => Err(ServerError::InvalidInput(format!("Expected a number, found {:?}", other))),
+    }
+  }
+
+  /// Consume any run of keep/drop/explode modifiers trailing a dice group,
+  /// e.g. the `kh1` and `!` in `4d6kh1!`. At most one keep/drop rule is
+  /// allowed per group; explode (`!`) may combine with it.
+  fn parse_modifiers(&mut self, count: u32) -> Result<(Option<KeepRule>, bool), ServerError>{
+    let mut keep = None;
+    let mut explode = false;
+
+    loop{
+      match self.peek(){
+        Some(Token::Ident(id)) if matches!(id.as_str(), "kh" | "kl" | "dh" | "dl") => {
+          let id = id.clone();
+          self.advance();
+          let n = self.expect_number()?;
+
+          if keep.is_some(){
+            return Err(ServerError::InvalidInput("Only one keep/drop modifier is allowed per dice group".to_string()));
+          }
+          if n > count{
+            return Err(ServerError::InvalidInput(format!("Cannot keep/drop {} dice from a group of {}", n, count)));
+          }
+
+          keep = Some(match id.as_str(){
+            "kh" => KeepRule::KeepHighest(n),
+            "kl" => KeepRule::KeepLowest(n),
+            "dh" => KeepRule::DropHighest(n),
+            "dl" => KeepRule::DropLowest(n),
+            _ => unreachable!(),
+          });
+        }
+        Some(Token::Bang) => {
+          self.advance();
+          explode = true;
+        }
+        _ => break,
+      }
+    }
+
+    Ok((keep, explode))
+  }
+
+  fn parse_term(&mut self) -> Result<Expr, ServerError>{
+    match self.peek(){
+      Some(Token::Number(n)) => {
+        let n = *n;
+        self.advance();
+        if matches!(self.peek(), Some(Token::Ident(id)) if id == "d"){
+          self.advance();
+          let sides = self.expect_number()?;
+          let (keep, explode) = self.parse_modifiers(n)?;
+          Ok(Expr::DiceGroup{count: n, sides, keep, explode})
+        }else{
+          Ok(Expr::Const(n as i64))
+        }
+      }
+      Some(Token::Ident(id)) if id == "d" => {
+        self.advance();
+        let sides = self.expect_number()?;
+        let (keep, explode) = self.parse_modifiers(1)?;
+        Ok(Expr::DiceGroup{count: 1, sides, keep, explode})
+      }
+      other => Err(ServerError::InvalidInput(format!("Expected a number or 'd', found {:?}", other))),
+    }
+  }
+
+  fn parse_expr(&mut self) -> Result<Expr, ServerError>{
+    let mut node = self.parse_term()?;
+
+    loop{
+      match self.peek(){
+        Some(Token::Plus) => {
+          self.advance();
+          let rhs = self.parse_term()?;
+          node = Expr::Add(Box::new(node), Box::new(rhs));
+        }
+        Some(Token::Minus) => {
+          self.advance();
+          let rhs = self.parse_term()?;
+          node = Expr::Sub(Box::new(node), Box::new(rhs));
+        }
+        _ => break,
+      }
+    }
+
+    Ok(node)
+  }
+}
+
+/// Replace every `$name` reference in `expr` with the dice-notation text
+/// stored for it in `session_id`'s session-variable store (see
+/// `auth::SessionStore`). A stored variable must resolve to a single term
+/// (a constant or a dice group, e.g. `"3d6"`) rather than a compound
+/// expression, since splicing `"3d6+1"` in place of a term after a `-`
+/// would change which part of it the minus sign applies to.
+#[cfg(feature = "auth-redis")]
+pub async fn resolve_variables(expr: &str, session_id: &str, store: &crate::auth::SessionStore) -> Result<String, ServerError>{
+  let mut resolved = String::with_capacity(expr.len());
+  let mut chars = expr.char_indices().peekable();
+
+  while let Some((_, c)) = chars.next(){
+    if c != '$'{
+      resolved.push(c);
+      continue;
+    }
+
+    let mut name = String::new();
+    while let Some(&(_, c)) = chars.peek(){
+      if c.is_alphanumeric() || c == '_'{
+        name.push(c);
+        chars.next();
+      }else{
+        break;
+      }
+    }
+    if name.is_empty(){
+      return Err(ServerError::InvalidInput("'$' must be followed by a variable name".to_string()));
+    }
+
+    let value = store.get(session_id, &name).await?
+      .ok_or_else(|| ServerError::InvalidInput(format!("Variable '${}' is not set for this session", name)))?;
+
+    match parse(&value)?{
+      Expr::Const(_) | Expr::DiceGroup{..} => resolved.push_str(&value),
+      _ => return Err(ServerError::InvalidInput(format!(
+        "Variable '${}' must be a single term (e.g. '3d6'), not a compound expression", name
+      ))),
+    }
+  }
+
+  Ok(resolved)
+}
+
+/// Parse a dice-notation expression like `2d6+1d8+3`, `d20-2`, or `4d6kh3!` into an AST.
+pub fn parse(input: &str) -> Result<Expr, ServerError>{
+  let tokens = tokenize(input)?;
+  if tokens.is_empty(){
+    return Err(ServerError::InvalidInput("Empty dice expression".to_string()));
+  }
+
+  let mut parser = Parser{tokens, pos: 0};
+  let expr = parser.parse_expr()?;
+
+  if parser.pos != parser.tokens.len(){
+    return Err(ServerError::InvalidInput(format!("Unexpected trailing input in '{}'", input)));
+  }
+
+  Ok(expr)
+}
+
+/// A single die's result, including any chain of explosions it triggered.
+#[derive(Debug, Clone)]
+pub struct RolledDie{
+  /// Every roll in this die's chain: `[first, explosion1, explosion2, ...]`.
+  pub chain: Vec<u32>,
+  /// Sum of the whole chain - what this die contributes if kept.
+  pub total: u32,
+}
+
+/// One evaluated term, carrying the sign it was rolled with so a breakdown
+/// can be rendered without re-walking the AST.
+#[derive(Debug)]
+pub enum EvaluatedTerm{
+  Const{sign: i64, value: i64},
+  /// `dice` holds every rolled die alongside whether a keep/drop rule kept
+  /// it - `(die, true)` contributed to the subtotal, `(die, false)` didn't.
+  Dice{sign: i64, count: u32, sides: u32, dice: Vec<(RolledDie, bool)>},
+}
+
+/// Grand total and per-term breakdown of an evaluated expression.
+pub struct Evaluation{
+  pub total: i64,
+  pub terms: Vec<EvaluatedTerm>,
+}
+
+/// Count every `DiceGroup` in `expr`, so the total-dice guard can be checked
+/// before any rolling happens. Saturates rather than wrapping, so a crafted
+/// multi-term expression with near-`u32::MAX` counts can't wrap back under
+/// `MAX_TOTAL_DICE` and sneak past the guard in `evaluate`.
+fn total_dice(expr: &Expr) -> u32{
+  match expr{
+    Expr::Const(_) => 0,
+    Expr::DiceGroup{count, ..} => *count,
+    Expr::Add(lhs, rhs) | Expr::Sub(lhs, rhs) => total_dice(lhs).saturating_add(total_dice(rhs)),
+  }
+}
+
+/// Roll one die of `sides`, exploding (rolling and adding another) whenever
+/// it shows `sides`, up to `MAX_EXPLOSIONS` times.
+fn roll_die(rng: &mut impl Rng, sides: u32, explode: bool) -> RolledDie{
+  let mut chain = vec![rng.random_range(1..=sides)];
+
+  if explode{
+    let mut explosions = 0;
+    while *chain.last().unwrap() == sides && explosions < MAX_EXPLOSIONS{
+      chain.push(rng.random_range(1..=sides));
+      explosions += 1;
+    }
+  }
+
+  let total = chain.iter().sum();
+  RolledDie{chain, total}
+}
+
+/// Mark which of `rolled`'s dice a keep/drop rule keeps, by sorting their
+/// totals rather than moving the dice themselves, so `rolled`'s original
+/// roll order is preserved for display.
+fn apply_keep_rule(rolled: &[RolledDie], keep: Option<KeepRule>) -> Vec<bool>{
+  let Some(rule) = keep else {
+    return vec![true; rolled.len()];
+  };
+
+  let n = rolled.len();
+  let mut by_total: Vec<usize> = (0..n).collect();
+  by_total.sort_by_key(|&i| rolled[i].total); // ascending: lowest first
+
+  let kept_indices: &[usize] = match rule{
+    KeepRule::KeepHighest(count) => &by_total[n.saturating_sub((count as usize).min(n))..],
+    KeepRule::KeepLowest(count) => &by_total[..(count as usize).min(n)],
+    KeepRule::DropHighest(count) => &by_total[..n.saturating_sub((count as usize).min(n))],
+    KeepRule::DropLowest(count) => &by_total[(count as usize).min(n)..],
+  };
+  let kept_indices: std::collections::HashSet<usize> = kept_indices.iter().copied().collect();
+
+  (0..n).map(|i| kept_indices.contains(&i)).collect()
+}
+
+/// Roll every `DiceGroup` in `expr` and sum the result, honoring each term's
+/// accumulated sign (flipped each time the path from the root enters the
+/// right-hand side of a `Sub`).
+fn collect(expr: &Expr, sign: i64, terms: &mut Vec<EvaluatedTerm>) -> Result<i64, ServerError>{
+  match expr{
+    Expr::Const(value) => {
+      terms.push(EvaluatedTerm::Const{sign, value: *value});
+      Ok(sign * value)
+    }
+    Expr::DiceGroup{count, sides, keep, explode} => {
+      if *sides == 0{
+        return Err(ServerError::InvalidInput("Dice must have at least 1 side".to_string()));
+      }
+
+      let mut rng = rand::rng();
+      let rolled: Vec<RolledDie> = (0..*count).map(|_| roll_die(&mut rng, *sides, *explode)).collect();
+      let kept_flags = apply_keep_rule(&rolled, *keep);
+
+      let subtotal: i64 = rolled.iter().zip(&kept_flags)
+        .filter(|(_, kept)| **kept)
+        .map(|(die, _)| die.total as i64)
+        .sum();
+
+      let dice = rolled.into_iter().zip(kept_flags).collect();
+      terms.push(EvaluatedTerm::Dice{sign, count: *count, sides: *sides, dice});
+      Ok(sign * subtotal)
+    }
+    Expr::Add(lhs, rhs) => Ok(collect(lhs, sign, terms)? + collect(rhs, sign, terms)?),
+    Expr::Sub(lhs, rhs) => Ok(collect(lhs, sign, terms)? + collect(rhs, -sign, terms)?),
+  }
+}
+
+/// Validate the total-dice guard, then roll every group and sum with signs.
+pub fn evaluate(expr: &Expr) -> Result<Evaluation, ServerError>{
+  let dice_count = total_dice(expr);
+  if dice_count == 0{
+    return Err(ServerError::InvalidInput("Expression must contain at least one dice group".to_string()));
+  }
+  if dice_count > MAX_TOTAL_DICE{
+    return Err(ServerError::InvalidInput(format!("Total dice across the expression must be at most {}", MAX_TOTAL_DICE)));
+  }
+
+  let mut terms = Vec::new();
+  let total = collect(expr, 1, &mut terms)?;
+
+  Ok(Evaluation{total, terms})
+}
+
+#[cfg(test)]
+mod tests{
+  use super::*;
+
+  #[test]
+  fn test_parse_simple_dice_group(){
+    assert_eq!(parse("d20").unwrap(), Expr::DiceGroup{count: 1, sides: 20, keep: None, explode: false});
+    assert_eq!(parse("2d6").unwrap(), Expr::DiceGroup{count: 2, sides: 6, keep: None, explode: false});
+  }
+
+  #[test]
+  fn test_parse_compound_expression(){
+    let expr = parse("2d6+1d8+3").unwrap();
+    assert_eq!(
+      expr,
+      Expr::Add(
+        Box::new(Expr::Add(
+          Box::new(Expr::DiceGroup{count: 2, sides: 6, keep: None, explode: false}),
+          Box::new(Expr::DiceGroup{count: 1, sides: 8, keep: None, explode: false}),
+        )),
+        Box::new(Expr::Const(3)),
+      )
+    );
+  }
+
+  #[test]
+  fn test_parse_rejects_garbage(){
+    assert!(parse("").is_err());
+    assert!(parse("2d").is_err());
+    assert!(parse("2d6 oops").is_err());
+  }
+
+  #[test]
+  fn test_parse_keep_and_explode_modifiers(){
+    assert_eq!(
+      parse("4d6kh3").unwrap(),
+      Expr::DiceGroup{count: 4, sides: 6, keep: Some(KeepRule::KeepHighest(3)), explode: false}
+    );
+    assert_eq!(
+      parse("4d6dl1!").unwrap(),
+      Expr::DiceGroup{count: 4, sides: 6, keep: Some(KeepRule::DropLowest(1)), explode: true}
+    );
+  }
+
+  #[test]
+  fn test_parse_rejects_keep_count_larger_than_group(){
+    assert!(parse("4d6kh5").is_err());
+  }
+
+  #[test]
+  fn test_evaluate_rejects_too_many_dice(){
+    let expr = parse("101d6").unwrap();
+    assert!(evaluate(&expr).is_err());
+  }
+
+  #[test]
+  fn test_evaluate_rejects_expression_with_no_dice(){
+    let expr = parse("5-3+2").unwrap();
+    assert!(evaluate(&expr).is_err(), "An all-constant expression has no dice group to roll");
+  }
+
+  #[test]
+  fn test_evaluate_sums_with_signs(){
+    // 1-sided dice always roll 1, so "2d1-1d1+3" is deterministic: 2 - 1 + 3 = 4.
+    let expr = parse("2d1-1d1+3").unwrap();
+    let evaluation = evaluate(&expr).unwrap();
+    assert_eq!(evaluation.total, 4);
+  }
+
+  #[test]
+  fn test_keep_highest_drops_the_rest(){
+    // 1-sided dice are all equal, so this just exercises the keep/drop bookkeeping.
+    let expr = parse("4d1kh2").unwrap();
+    let evaluation = evaluate(&expr).unwrap();
+    assert_eq!(evaluation.total, 2);
+
+    let EvaluatedTerm::Dice{dice, ..} = &evaluation.terms[0] else { panic!("expected a dice term") };
+    assert_eq!(dice.iter().filter(|(_, kept)| *kept).count(), 2);
+    assert_eq!(dice.iter().filter(|(_, kept)| !*kept).count(), 2);
+  }
+
+  #[test]
+  fn test_exploding_die_is_capped(){
+    // A d1 always shows its max face, so an exploding d1 must hit the hard
+    // cap rather than looping forever.
+    let mut rng = rand::rng();
+    let die = roll_die(&mut rng, 1, true);
+    assert_eq!(die.chain.len() as u32, MAX_EXPLOSIONS + 1);
+    assert_eq!(die.total, MAX_EXPLOSIONS + 1);
+  }
+}
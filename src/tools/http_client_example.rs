@@ -6,6 +6,16 @@ use crate::auth::AuthService;
 
 #[cfg(feature = "http-client")]
 use reqwest::Client;
+#[cfg(feature = "http-client")]
+use rand::Rng;
+#[cfg(feature = "http-client")]
+use std::time::Duration;
+#[cfg(feature = "http-client")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "http-client")]
+use sha2::{Digest, Sha256};
+#[cfg(feature = "http-client")]
+use futures_util::StreamExt;
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct AuthenticatedApiRequest {
@@ -21,6 +31,10 @@ pub struct AuthenticatedApiRequest {
     pub method: String,
     /// Optional JSON body for POST/PUT requests
     pub body: Option<serde_json::Value>,
+    /// POST isn't inherently idempotent, so it's only retried when this is
+    /// explicitly set - GET/PUT/DELETE are retried regardless.
+    #[serde(default)]
+    pub retry_post: bool,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -32,6 +46,10 @@ pub struct PublicApiRequest {
     pub method: String,
     /// Optional JSON body for POST/PUT requests
     pub body: Option<serde_json::Value>,
+    /// POST isn't inherently idempotent, so it's only retried when this is
+    /// explicitly set - GET/PUT/DELETE are retried regardless.
+    #[serde(default)]
+    pub retry_post: bool,
 }
 
 fn default_provider() -> String {
@@ -42,11 +60,78 @@ fn default_method() -> String {
     "GET".to_string()
 }
 
+/// One named field of a multipart upload. Exactly one of `text`, `base64_data`,
+/// or `file_path` must be set; `file_path` is streamed from disk rather than
+/// being read into memory up front, so large files stay memory-bounded.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MultipartFieldInput {
+    /// Form field name
+    pub name: String,
+    /// Inline text value
+    pub text: Option<String>,
+    /// Standard (RFC 4648, padded) base64-encoded binary value
+    pub base64_data: Option<String>,
+    /// Path to a file on disk to stream as this field's body
+    pub file_path: Option<String>,
+    /// Filename reported to the server. Defaults to `file_path`'s last
+    /// component, or is omitted for `text`/`base64_data` fields.
+    pub filename: Option<String>,
+    /// Content-Type reported for this field
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MultipartUploadRequest {
+    /// API endpoint URL to receive the upload
+    pub url: String,
+    /// Named multipart form fields to send
+    pub fields: Vec<MultipartFieldInput>,
+    /// Session ID for an authenticated upload (UUID4 format). Omit to upload
+    /// without authentication.
+    pub session_id: Option<String>,
+    /// OAuth provider for token, when `session_id` is set
+    #[serde(default = "default_provider")]
+    pub provider: String,
+}
+
+/// Retry policy for transient failures: connection errors, 5xx, and 429.
+#[cfg(feature = "http-client")]
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+#[cfg(feature = "http-client")]
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+#[cfg(feature = "http-client")]
+impl From<&crate::config::HttpClientConfig> for RetryConfig {
+    fn from(config: &crate::config::HttpClientConfig) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            base_delay: Duration::from_millis(config.base_delay_ms),
+            max_delay: Duration::from_millis(config.max_delay_ms),
+        }
+    }
+}
+
 /// Example tool that demonstrates HTTP client patterns with authentication
 #[derive(Clone)]
 pub struct HttpClientExampleTool {
     #[cfg(feature = "http-client")]
     client: Client,
+    #[cfg(feature = "http-client")]
+    retry: RetryConfig,
     #[cfg(feature = "auth")]
     auth_service: AuthService,
     #[cfg(not(feature = "auth"))]
@@ -60,6 +145,7 @@ impl HttpClientExampleTool {
     pub fn new(auth_service: AuthService) -> Self {
         Self {
             client: Client::new(),
+            retry: RetryConfig::default(),
             auth_service,
         }
     }
@@ -68,6 +154,7 @@ impl HttpClientExampleTool {
     pub fn new(_auth_service: AuthService) -> Self {
         Self {
             client: Client::new(),
+            retry: RetryConfig::default(),
             _auth_phantom: std::marker::PhantomData,
         }
     }
@@ -88,6 +175,54 @@ impl HttpClientExampleTool {
         }
     }
 
+    /// Override the default retry policy (3 retries, 200ms base, 10s cap).
+    #[cfg(feature = "http-client")]
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Send a request built fresh by `build_request` on each attempt, retrying
+    /// connection errors, 5xx, and 429 on idempotent methods (GET/PUT/DELETE,
+    /// and POST only when `retry_post` opts in). 429/503 honor `Retry-After`;
+    /// other transient failures back off exponentially with full jitter.
+    #[cfg(feature = "http-client")]
+    async fn send_with_retry(
+        &self,
+        method: &str,
+        retry_post: bool,
+        build_request: impl Fn() -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        let retryable_method = matches!(method, "GET" | "PUT" | "DELETE") || (method == "POST" && retry_post);
+
+        let mut attempt = 0;
+        loop {
+            let result = build_request().send().await;
+
+            let is_transient = match &result {
+                Ok(response) => response.status().is_server_error() || response.status().as_u16() == 429,
+                Err(e) => e.is_connect() || e.is_timeout(),
+            };
+
+            if !retryable_method || !is_transient || attempt >= self.retry.max_retries {
+                return result;
+            }
+
+            let delay = match &result {
+                Ok(response) => retry_after_delay(response),
+                Err(_) => None,
+            }
+            .unwrap_or_else(|| backoff_delay(self.retry.base_delay, self.retry.max_delay, attempt));
+
+            attempt += 1;
+            tracing::warn!(
+                "Retrying {} request (attempt {}/{}) after {:?}",
+                method, attempt, self.retry.max_retries, delay
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+
     /// Make an authenticated API call using OAuth token
     pub async fn authenticated_api_call(&self, req: AuthenticatedApiRequest) -> Result<CallToolResult, McpError> {
         tracing::info!("🌐 authenticated_api_call to: {}", req.url);
@@ -116,26 +251,30 @@ impl HttpClientExampleTool {
                     )
                 })?;
 
-            // Build HTTP request
-            let mut request_builder = match req.method.to_uppercase().as_str() {
-                "GET" => self.client.get(&req.url),
-                "POST" => self.client.post(&req.url),
-                "PUT" => self.client.put(&req.url),
-                "DELETE" => self.client.delete(&req.url),
-                _ => return Err(McpError::invalid_params("Unsupported HTTP method", None)),
-            };
-
-            // Add OAuth bearer token
-            request_builder = request_builder.bearer_auth(&token_data.access_token);
-
-            // Add JSON body if provided
-            if let Some(body) = req.body {
-                request_builder = request_builder.json(&body);
+            let method = req.method.to_uppercase();
+            if !matches!(method.as_str(), "GET" | "POST" | "PUT" | "DELETE") {
+                return Err(McpError::invalid_params("Unsupported HTTP method", None));
             }
 
-            // Make the request
-            let response = request_builder
-                .send()
+            let access_token = token_data.access_token.clone();
+            let body = req.body.clone();
+
+            // Make the request, retrying transient failures
+            let response = self
+                .send_with_retry(&method, req.retry_post, || {
+                    let mut builder = match method.as_str() {
+                        "GET" => self.client.get(&req.url),
+                        "POST" => self.client.post(&req.url),
+                        "PUT" => self.client.put(&req.url),
+                        "DELETE" => self.client.delete(&req.url),
+                        _ => unreachable!(),
+                    };
+                    builder = builder.bearer_auth(&access_token);
+                    if let Some(body) = &body {
+                        builder = builder.json(body);
+                    }
+                    builder
+                })
                 .await
                 .map_err(|e| McpError::internal_error(format!("HTTP request failed: {}", e), None))?;
 
@@ -203,23 +342,28 @@ impl HttpClientExampleTool {
 
         #[cfg(feature = "http-client")]
         {
-            // Build HTTP request
-            let mut request_builder = match req.method.to_uppercase().as_str() {
-                "GET" => self.client.get(&req.url),
-                "POST" => self.client.post(&req.url),
-                "PUT" => self.client.put(&req.url),
-                "DELETE" => self.client.delete(&req.url),
-                _ => return Err(McpError::invalid_params("Unsupported HTTP method", None)),
-            };
-
-            // Add JSON body if provided
-            if let Some(body) = req.body {
-                request_builder = request_builder.json(&body);
+            let method = req.method.to_uppercase();
+            if !matches!(method.as_str(), "GET" | "POST" | "PUT" | "DELETE") {
+                return Err(McpError::invalid_params("Unsupported HTTP method", None));
             }
 
-            // Make the request
-            let response = request_builder
-                .send()
+            let body = req.body.clone();
+
+            // Make the request, retrying transient failures
+            let response = self
+                .send_with_retry(&method, req.retry_post, || {
+                    let mut builder = match method.as_str() {
+                        "GET" => self.client.get(&req.url),
+                        "POST" => self.client.post(&req.url),
+                        "PUT" => self.client.put(&req.url),
+                        "DELETE" => self.client.delete(&req.url),
+                        _ => unreachable!(),
+                    };
+                    if let Some(body) = &body {
+                        builder = builder.json(body);
+                    }
+                    builder
+                })
                 .await
                 .map_err(|e| McpError::internal_error(format!("HTTP request failed: {}", e), None))?;
 
@@ -268,6 +412,255 @@ impl HttpClientExampleTool {
             Ok(CallToolResult::success(vec![Content::text(result_text)]))
         }
     }
+
+    /// Upload named fields as a `multipart/form-data` request, streaming
+    /// `file_path` fields from disk instead of buffering them whole. Unlike
+    /// `authenticated_api_call`/`public_api_call`, this isn't retried - once a
+    /// streamed field has been read it can't be replayed without reopening the
+    /// file, so a single `send()` keeps memory bounded without pretending a
+    /// partially-sent upload can be safely resumed.
+    pub async fn multipart_upload(&self, req: MultipartUploadRequest) -> Result<CallToolResult, McpError> {
+        tracing::info!("📦 multipart_upload to: {}", req.url);
+
+        #[cfg(feature = "http-client")]
+        {
+            #[cfg(feature = "auth")]
+            let bearer_token = match &req.session_id {
+                Some(session_id) => {
+                    if let Err(e) = AuthService::validate_session_format(session_id) {
+                        tracing::error!("❌ Invalid session ID format: {}", e);
+                        return Err(McpError::invalid_params(format!("Invalid session ID: {}", e), None));
+                    }
+                    let token_data = self.auth_service.authenticate(session_id, &req.provider).await.map_err(|e| {
+                        tracing::error!("❌ Authentication failed: {}", e);
+                        McpError::invalid_params(format!("Authentication failed: {}", e), None)
+                    })?;
+                    Some(token_data.access_token)
+                }
+                None => None,
+            };
+            #[cfg(not(feature = "auth"))]
+            let bearer_token: Option<String> = None;
+
+            let (form, hashes) = build_multipart_form(&req.fields).await?;
+
+            let mut builder = self.client.post(&req.url).multipart(form);
+            if let Some(token) = &bearer_token {
+                builder = builder.bearer_auth(token);
+            }
+
+            let response = builder
+                .send()
+                .await
+                .map_err(|e| McpError::internal_error(format!("HTTP request failed: {}", e), None))?;
+
+            let status = response.status();
+            let response_text = response
+                .text()
+                .await
+                .map_err(|e| McpError::internal_error(format!("Failed to read response: {}", e), None))?;
+
+            // Every field has finished streaming by the time `send` returns,
+            // so the hashes below reflect exactly what was sent.
+            let field_summary = hashes
+                .into_iter()
+                .map(|(name, hash)| format!("  - {}: sha256={}", name, hash.finalize_hex()))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            let result_text = format!(
+                "Multipart Upload Results:\n\
+                 • URL: {}\n\
+                 • Status: {}\n\
+                 • Authentication: {}\n\
+                 • Fields:\n{}\n\
+                 • Response Length: {} bytes",
+                req.url,
+                status,
+                if bearer_token.is_some() { "Enabled" } else { "None" },
+                field_summary,
+                response_text.len(),
+            );
+
+            tracing::info!("✅ Multipart upload completed with status: {}", status);
+            Ok(CallToolResult::success(vec![Content::text(result_text)]))
+        }
+
+        #[cfg(not(feature = "http-client"))]
+        {
+            let result_text = format!(
+                "Multipart Upload:\n\
+                 • URL: {}\n\
+                 • Status: Feature Not Available\n\
+                 • Missing: http-client feature\n\
+                 • Note: Enable 'http-client' feature for HTTP functionality",
+                req.url
+            );
+
+            tracing::info!("⚠️ Multipart upload requested but http-client feature not enabled");
+            Ok(CallToolResult::success(vec![Content::text(result_text)]))
+        }
+    }
+}
+
+/// Exponential backoff with full jitter: `rand(0, min(max, base * 2^attempt))`.
+#[cfg(feature = "http-client")]
+fn backoff_delay(base: Duration, max: Duration, attempt: u32) -> Duration {
+    let exponential = base.as_millis().saturating_mul(1u128 << attempt.min(32));
+    let capped = exponential.min(max.as_millis()).max(1);
+    let jittered = rand::rng().random_range(0..=capped);
+    Duration::from_millis(jittered as u64)
+}
+
+/// Parse a 429/503 response's `Retry-After` header, in either delta-seconds
+/// or HTTP-date form. Returns `None` for any other status or an unparseable/absent header.
+#[cfg(feature = "http-client")]
+fn retry_after_delay(response: &reqwest::Response) -> Option<Duration> {
+    let status = response.status().as_u16();
+    if status != 429 && status != 503 {
+        return None;
+    }
+
+    let value = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let when = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (when.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().ok()
+}
+
+/// A multipart field's content hash: computed eagerly for inline fields, or
+/// accumulated in a shared hasher as a streamed file field is read, so the
+/// hash reflects bytes as they pass through rather than a separate re-read.
+#[cfg(feature = "http-client")]
+enum FieldHash {
+    Ready(String),
+    Streaming(Arc<Mutex<Sha256>>),
+}
+
+#[cfg(feature = "http-client")]
+impl FieldHash {
+    fn finalize_hex(self) -> String {
+        match self {
+            FieldHash::Ready(hex) => hex,
+            FieldHash::Streaming(hasher) => {
+                let hasher = Arc::try_unwrap(hasher)
+                    .unwrap_or_else(|shared| Mutex::new(shared.lock().unwrap().clone()));
+                hex_encode(&hasher.into_inner().unwrap().finalize())
+            }
+        }
+    }
+}
+
+/// Build a `multipart/form-data` body from `fields`, streaming `file_path`
+/// fields straight from disk (via `Part::stream`) instead of reading them
+/// into memory first, the way a backup service tees a file onward to its
+/// destination while hashing it in the same pass.
+#[cfg(feature = "http-client")]
+async fn build_multipart_form(
+    fields: &[MultipartFieldInput],
+) -> Result<(reqwest::multipart::Form, Vec<(String, FieldHash)>), McpError> {
+    let mut form = reqwest::multipart::Form::new();
+    let mut hashes = Vec::with_capacity(fields.len());
+
+    for field in fields {
+        let set_count = [field.text.is_some(), field.base64_data.is_some(), field.file_path.is_some()]
+            .into_iter()
+            .filter(|set| *set)
+            .count();
+        if set_count != 1 {
+            return Err(McpError::invalid_params(
+                format!("Field '{}' must set exactly one of text, base64_data, or file_path", field.name),
+                None,
+            ));
+        }
+
+        let (mut part, hash) = if let Some(text) = &field.text {
+            let hash = FieldHash::Ready(hex_encode(&Sha256::digest(text.as_bytes())));
+            (reqwest::multipart::Part::text(text.clone()), hash)
+        } else if let Some(base64_data) = &field.base64_data {
+            let bytes = base64_decode(base64_data)
+                .map_err(|e| McpError::invalid_params(format!("Invalid base64_data for '{}': {}", field.name, e), None))?;
+            let hash = FieldHash::Ready(hex_encode(&Sha256::digest(&bytes)));
+            (reqwest::multipart::Part::bytes(bytes), hash)
+        } else {
+            let file_path = field.file_path.as_ref().unwrap();
+            let file = tokio::fs::File::open(file_path).await.map_err(|e| {
+                McpError::invalid_params(format!("Cannot open file '{}' for field '{}': {}", file_path, field.name, e), None)
+            })?;
+
+            let hasher = Arc::new(Mutex::new(Sha256::new()));
+            let hasher_for_stream = hasher.clone();
+            let stream = tokio_util::io::ReaderStream::new(file).map(move |chunk| {
+                if let Ok(bytes) = &chunk {
+                    hasher_for_stream.lock().unwrap().update(bytes);
+                }
+                chunk
+            });
+
+            let part = reqwest::multipart::Part::stream(reqwest::Body::wrap_stream(stream));
+            let filename = field.filename.clone().unwrap_or_else(|| {
+                std::path::Path::new(file_path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| field.name.clone())
+            });
+            (part.file_name(filename), FieldHash::Streaming(hasher))
+        };
+
+        if field.file_path.is_none() {
+            if let Some(filename) = &field.filename {
+                part = part.file_name(filename.clone());
+            }
+        }
+
+        if let Some(content_type) = &field.content_type {
+            part = part
+                .mime_str(content_type)
+                .map_err(|e| McpError::invalid_params(format!("Invalid content_type for '{}': {}", field.name, e), None))?;
+        }
+
+        form = form.part(field.name.clone(), part);
+        hashes.push((field.name.clone(), hash));
+    }
+
+    Ok((form, hashes))
+}
+
+/// RFC 4648 base64 decoding (standard alphabet, `=`-padded). Not the inverse
+/// of the url-safe encoder in `auth::oauth`, which is unrelated (PKCE
+/// challenges vs. inline binary field values here).
+#[cfg(feature = "http-client")]
+fn base64_decode(input: &str) -> Result<Vec<u8>, String> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let input = input.trim().trim_end_matches('=');
+    let mut out = Vec::with_capacity(input.len() * 3 / 4 + 3);
+    let mut buffer: u32 = 0;
+    let mut bits: u32 = 0;
+
+    for c in input.bytes() {
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a == c)
+            .ok_or_else(|| format!("Invalid base64 character: {}", c as char))? as u32;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(feature = "http-client")]
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
 }
 
 #[cfg(test)]
@@ -291,6 +684,7 @@ mod tests {
             provider: "google".to_string(),
             method: "GET".to_string(),
             body: None,
+            retry_post: false,
         };
 
         let result = tool.authenticated_api_call(invalid_req).await;
@@ -307,9 +701,53 @@ mod tests {
             url: "https://httpbin.org/get".to_string(),
             method: "INVALID".to_string(),
             body: None,
+            retry_post: false,
         };
 
         let result = tool.public_api_call(invalid_req).await;
         assert!(result.is_err(), "Should fail with invalid HTTP method");
     }
+
+    #[cfg(feature = "http-client")]
+    #[test]
+    fn test_backoff_delay_respects_cap() {
+        let base = Duration::from_millis(100);
+        let max = Duration::from_secs(1);
+
+        for attempt in 0..10 {
+            let delay = backoff_delay(base, max, attempt);
+            assert!(delay <= max);
+        }
+    }
+
+    #[cfg(feature = "http-client")]
+    #[test]
+    fn test_retry_config_default() {
+        let retry = RetryConfig::default();
+        assert_eq!(retry.max_retries, 3);
+    }
+
+    #[cfg(feature = "http-client")]
+    #[test]
+    fn test_base64_decode_known_vector() {
+        // "hello" base64-encoded with standard padding
+        let decoded = base64_decode("aGVsbG8=").unwrap();
+        assert_eq!(decoded, b"hello");
+    }
+
+    #[cfg(feature = "http-client")]
+    #[tokio::test]
+    async fn test_multipart_requires_exactly_one_value() {
+        let fields = vec![MultipartFieldInput {
+            name: "file".to_string(),
+            text: Some("a".to_string()),
+            base64_data: Some("YQ==".to_string()),
+            file_path: None,
+            filename: None,
+            content_type: None,
+        }];
+
+        let result = build_multipart_form(&fields).await;
+        assert!(result.is_err(), "Should reject a field with more than one value source set");
+    }
 }
\ No newline at end of file
@@ -1,5 +1,8 @@
 pub mod dice_example;
 
+#[cfg(feature = "admin")]
+pub mod admin_example;
+
 #[cfg(feature = "auth")]
 pub mod auth_example;
 
@@ -13,9 +13,25 @@ async fn test_server_creation() {
       format: {{crate_name}}::config::LogFormat::Pretty,
       file: None,
     },
+    auth: Default::default(),
+    #[cfg(feature = "auth")]
+    redis: None,
+    #[cfg(feature = "auth-jwt")]
+    jwt: None,
+    #[cfg(feature = "database")]
+    database: None,
+    #[cfg(feature = "http-client")]
+    http_client: None,
+    #[cfg(feature = "admin")]
+    admin: None,
+    #[cfg(feature = "admin")]
+    config_path: None,
   };
 
   // Test server creation - this should work without any complex setup
+  #[cfg(feature = "admin")]
+  let server = Server::new(config, test_reload_handle()).await;
+  #[cfg(not(feature = "admin"))]
   let server = Server::new(config).await;
   assert!(server.is_ok(), "Server creation should succeed");
 
@@ -37,8 +53,35 @@ async fn test_config_validation() {
       format: {{crate_name}}::config::LogFormat::Json,
       file: Some("/tmp/test.log".to_string()),
     },
+    auth: Default::default(),
+    #[cfg(feature = "auth")]
+    redis: None,
+    #[cfg(feature = "auth-jwt")]
+    jwt: None,
+    #[cfg(feature = "database")]
+    database: None,
+    #[cfg(feature = "http-client")]
+    http_client: None,
+    #[cfg(feature = "admin")]
+    admin: None,
+    #[cfg(feature = "admin")]
+    config_path: None,
   };
 
+  #[cfg(feature = "admin")]
+  let server = Server::new(config, test_reload_handle()).await;
+  #[cfg(not(feature = "admin"))]
   let server = Server::new(config).await;
   assert!(server.is_ok(), "Server should handle different config options");
 }
+
+/// A throwaway `LevelReloadHandle` for tests that need to construct a
+/// `Server` but never call its admin `reload_config` tool - building a real
+/// one means duplicating `telemetry::init`'s non-blocking writer setup for
+/// no benefit, and calling `telemetry::init_with_reload` itself would panic
+/// the second time a test in this binary called it (the subscriber it
+/// installs is process-global and can only be set once).
+#[cfg(feature = "admin")]
+fn test_reload_handle() -> {{crate_name}}::telemetry::LevelReloadHandle {
+  tracing_subscriber::reload::Layer::new(tracing_subscriber::EnvFilter::new("info")).1
+}